@@ -0,0 +1,248 @@
+//! Load generator for the Space Race server.
+//!
+//! Registers a crowd of synthetic players, polls `/state` and posts `/control` for each of
+//! them at a fixed rate, then reports request latencies, error rates and how the races went.
+
+mod stats;
+
+use anyhow::{anyhow, bail};
+use rand::distributions::DistString;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use spacerace_client::bot::{Autopilot, Bot, ShipInput};
+use spacerace_client::protocol::{GameStatus, PlayerRegistration, PublicGameState, Ship};
+use spacerace_client::{SpaceRaceClient, DEFAULT_SERVER};
+use stats::{Outcome, Stats};
+use std::env;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::info;
+
+const USAGE: &str = "Usage: loadtest [--players N] [--rate HZ] [--duration SECONDS] \
+[--inputs random|sweep|autopilot] [--map NAME] [--server URL]";
+
+struct Config {
+    server: String,
+    players: usize,
+    /// Time between polls and control requests, per player
+    period: Duration,
+    duration: Duration,
+    inputs: Inputs,
+    map: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Inputs {
+    /// A new random thrust and rotation every request
+    Random,
+    /// Full thrust while sweeping the rotation back and forth
+    Sweep,
+    /// Fly the route to the finish, so races actually end
+    Autopilot,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let config = Arc::new(parse_args(env::args().skip(1))?);
+    let stats = Arc::new(Mutex::new(Stats::default()));
+    let started = Instant::now();
+    let deadline = started + config.duration;
+    info!(
+        players = config.players,
+        period = ?config.period,
+        server = config.server,
+        "Starting load test"
+    );
+
+    let players: Vec<_> = (0..config.players)
+        .map(|index| tokio::spawn(run_player(index, config.clone(), stats.clone(), deadline)))
+        .collect();
+    for player in players {
+        player.await?;
+    }
+
+    println!("{}", stats.lock().unwrap().report(started.elapsed()));
+    Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<Config> {
+    let mut config = Config {
+        server: env::var("SPACERACERS_SERVER").unwrap_or_else(|_| DEFAULT_SERVER.to_string()),
+        players: 10,
+        period: Duration::from_millis(100),
+        duration: Duration::from_secs(60),
+        inputs: Inputs::Random,
+        map: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("{arg} needs a value\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--players" => config.players = value()?.parse()?,
+            "--rate" => {
+                let rate: f32 = value()?.parse()?;
+                // Rates too low or too high for a period give an error or 0 here
+                config.period = Duration::try_from_secs_f32(1.0 / rate)
+                    .ok()
+                    .filter(|period| !period.is_zero())
+                    .ok_or_else(|| anyhow!("--rate must be a positive number, {rate} isn't"))?
+            }
+            "--duration" => {
+                config.duration = Duration::try_from_secs_f32(value()?.parse()?)
+                    .map_err(|_| anyhow!("--duration must be a number of seconds, 0 or more"))?
+            }
+            "--inputs" => {
+                config.inputs = match value()?.as_str() {
+                    "random" => Inputs::Random,
+                    "sweep" => Inputs::Sweep,
+                    "autopilot" => Inputs::Autopilot,
+                    other => bail!("unknown inputs {other}\n{USAGE}"),
+                }
+            }
+            "--map" => config.map = Some(value()?),
+            "--server" => config.server = value()?,
+            "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            other => bail!("unknown option {other}\n{USAGE}"),
+        }
+    }
+    Ok(config)
+}
+
+/// Registers one synthetic player and drives its ship until its game ends or the load test
+/// is over.
+async fn run_player(
+    index: usize,
+    config: Arc<Config>,
+    stats: Arc<Mutex<Stats>>,
+    deadline: Instant,
+) {
+    // Errors are what we're here to count, so don't retry them away
+    let client = SpaceRaceClient::new(&config.server).retries(0, Duration::ZERO);
+    let mut rng = StdRng::from_entropy();
+    let password = rand::distributions::Alphanumeric.sample_string(&mut rng, 16);
+    let registration = PlayerRegistration {
+        name: format!("load-{index}"),
+        team: Some("load".to_string()),
+        password: password.clone(),
+        map: config.map.clone(),
+        ship_class: None,
+        ship_contact: None,
+    };
+
+    let Some(registration) = timed(&stats, "lobby", client.register(&registration)).await else {
+        stats.lock().unwrap().outcome(Outcome::NotRegistered);
+        return;
+    };
+    let mut pilot: Box<dyn Bot + Send> = match config.inputs {
+        Inputs::Random => Box::new(RandomPilot(rng)),
+        Inputs::Sweep => Box::new(SweepPilot),
+        Inputs::Autopilot => Box::new(Autopilot::default()),
+    };
+
+    let mut ticker = interval(config.period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_state: Option<PublicGameState> = None;
+    let outcome = loop {
+        ticker.tick().await;
+        if Instant::now() >= deadline {
+            break match last_state {
+                Some(_) => Outcome::StillRacing,
+                None => Outcome::NeverRaced,
+            };
+        }
+
+        let game_id = registration.game_id;
+        let Some(state) = timed(&stats, "state", client.game_state(game_id)).await else {
+            continue;
+        };
+        let state = match (state, &last_state) {
+            (Some(state), _) => state,
+            // Our game has been and gone
+            (None, Some(last)) => break outcome(last, registration.ship_id),
+            (None, None) => continue,
+        };
+
+        if last_state.is_none() {
+            if let Some(Some(map)) = timed(&stats, "map", client.map(game_id)).await {
+                pilot.on_game_start(&map);
+            }
+        }
+        if state.state == GameStatus::Finished {
+            break outcome(&state, registration.ship_id);
+        }
+
+        let ship = state
+            .ships
+            .iter()
+            .find(|ship| Some(ship.id) == registration.ship_id);
+        if let (GameStatus::Running, Some(ship)) = (&state.state, ship) {
+            let input = pilot.on_tick(&state, ship);
+            let control = client.control(&password, input.thrust, input.rotation);
+            timed(&stats, "control", control).await;
+        }
+        last_state = Some(state);
+    };
+    stats.lock().unwrap().outcome(outcome);
+}
+
+/// Times a request, recording its latency and whether it failed.
+async fn timed<T, E>(
+    stats: &Mutex<Stats>,
+    endpoint: &'static str,
+    request: impl Future<Output = Result<T, E>>,
+) -> Option<T> {
+    let start = Instant::now();
+    let result = request.await;
+    stats
+        .lock()
+        .unwrap()
+        .record(endpoint, start.elapsed(), result.is_ok());
+    result.ok()
+}
+
+fn outcome(state: &PublicGameState, ship_id: Option<uuid::Uuid>) -> Outcome {
+    let ship_id = ship_id.unwrap_or_default();
+    match state.finish_times.get(&ship_id) {
+        Some(time) => Outcome::Finished(*time),
+        None if state.did_not_finish.contains(&ship_id) => Outcome::DidNotFinish,
+        None if state.state == GameStatus::Finished => Outcome::DidNotFinish,
+        None => Outcome::StillRacing,
+    }
+}
+
+struct RandomPilot(StdRng);
+
+impl Bot for RandomPilot {
+    fn on_tick(&mut self, _state: &PublicGameState, _ship: &Ship) -> ShipInput {
+        ShipInput {
+            thrust: self.0.gen_range(-1.0..=1.0),
+            rotation: self.0.gen_range(-1.0..=1.0),
+        }
+    }
+}
+
+struct SweepPilot;
+
+impl Bot for SweepPilot {
+    fn on_tick(&mut self, state: &PublicGameState, _ship: &Ship) -> ShipInput {
+        ShipInput {
+            thrust: 1.0,
+            rotation: (state.tick as f32 / 60.0).sin(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
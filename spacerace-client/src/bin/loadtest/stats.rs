@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// How a synthetic player's run ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// Crossed the finish line, with its race time in seconds
+    Finished(f32),
+    /// Knocked out of the race
+    DidNotFinish,
+    /// The game was still running when the load test ended
+    StillRacing,
+    /// The player's game never started before the load test ended
+    NeverRaced,
+    /// The lobby turned the player away or couldn't be reached
+    NotRegistered,
+}
+
+#[derive(Default)]
+struct EndpointStats {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+/// Request latencies and errors by endpoint, and how each player's race went.
+#[derive(Default)]
+pub struct Stats {
+    endpoints: BTreeMap<&'static str, EndpointStats>,
+    outcomes: Vec<Outcome>,
+}
+
+impl Stats {
+    pub fn record(&mut self, endpoint: &'static str, latency: Duration, ok: bool) {
+        let stats = self.endpoints.entry(endpoint).or_default();
+        stats.latencies.push(latency);
+        if !ok {
+            stats.errors += 1;
+        }
+    }
+
+    pub fn outcome(&mut self, outcome: Outcome) {
+        self.outcomes.push(outcome);
+    }
+
+    pub fn report(&mut self, elapsed: Duration) -> String {
+        let mut report = String::new();
+        let seconds = elapsed.as_secs_f32().max(f32::EPSILON);
+        writeln!(
+            report,
+            "{:<10} {:>8} {:>8} {:>7} {:>8} {:>8} {:>8} {:>8}",
+            "endpoint", "requests", "req/s", "errors", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )
+        .unwrap();
+        for (endpoint, stats) in &mut self.endpoints {
+            stats.latencies.sort();
+            let requests = stats.latencies.len();
+            let ms = |p| percentile(&stats.latencies, p).as_secs_f64() * 1000.0;
+            writeln!(
+                report,
+                "{:<10} {:>8} {:>8.1} {:>6.1}% {:>8.1} {:>8.1} {:>8.1} {:>8.1}",
+                endpoint,
+                requests,
+                requests as f32 / seconds,
+                100.0 * stats.errors as f32 / requests.max(1) as f32,
+                ms(50.0),
+                ms(90.0),
+                ms(99.0),
+                ms(100.0),
+            )
+            .unwrap();
+        }
+
+        let count = |wanted: Outcome| self.outcomes.iter().filter(|&&o| o == wanted).count();
+        let mut times: Vec<f32> = self
+            .outcomes
+            .iter()
+            .filter_map(|outcome| match outcome {
+                Outcome::Finished(time) => Some(*time),
+                _ => None,
+            })
+            .collect();
+        times.sort_by(f32::total_cmp);
+        writeln!(
+            report,
+            "\n{} players: {} finished, {} did not finish, {} still racing, {} never raced, {} not registered",
+            self.outcomes.len(),
+            times.len(),
+            count(Outcome::DidNotFinish),
+            count(Outcome::StillRacing),
+            count(Outcome::NeverRaced),
+            count(Outcome::NotRegistered),
+        )
+        .unwrap();
+        if let (Some(best), Some(worst)) = (times.first(), times.last()) {
+            let mean = times.iter().sum::<f32>() / times.len() as f32;
+            writeln!(
+                report,
+                "finish times: best {best:.2}s, mean {mean:.2}s, worst {worst:.2}s"
+            )
+            .unwrap();
+        }
+        report
+    }
+}
+
+/// The nearest-rank percentile of sorted latencies, zero when there are none.
+pub fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
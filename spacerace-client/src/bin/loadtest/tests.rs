@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use crate::parse_args;
+use crate::stats::{percentile, Outcome, Stats};
+
+#[test]
+fn test_percentiles_use_nearest_rank() {
+    let latencies: Vec<Duration> = (1..=10).map(Duration::from_millis).collect();
+
+    assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(5));
+    assert_eq!(percentile(&latencies, 90.0), Duration::from_millis(9));
+    assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(10));
+    assert_eq!(percentile(&latencies, 0.0), Duration::from_millis(1));
+    assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+}
+
+#[test]
+fn test_report_summarises_requests_and_races() {
+    let mut stats = Stats::default();
+    for (ms, ok) in [(30, true), (10, true), (20, false), (40, true)] {
+        stats.record("state", Duration::from_millis(ms), ok);
+    }
+    stats.outcome(Outcome::Finished(40.0));
+    stats.outcome(Outcome::Finished(50.0));
+    stats.outcome(Outcome::NeverRaced);
+
+    let report = stats.report(Duration::from_secs(2));
+
+    let state = report.lines().find(|l| l.starts_with("state")).unwrap();
+    let columns: Vec<&str> = state.split_whitespace().collect();
+    assert_eq!(
+        columns,
+        ["state", "4", "2.0", "25.0%", "20.0", "40.0", "40.0", "40.0"]
+    );
+    assert!(
+        report.contains("3 players: 2 finished, 0 did not finish, 0 still racing, 1 never raced")
+    );
+    assert!(report.contains("best 40.00s, mean 45.00s, worst 50.00s"));
+}
+
+#[test]
+fn test_args_must_be_usable_numbers() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+    let config = parse(&["--rate", "4", "--duration", "90"]).unwrap();
+    assert_eq!(config.period, Duration::from_millis(250));
+    assert_eq!(config.duration, Duration::from_secs(90));
+
+    for args in [
+        ["--rate", "NaN"],
+        ["--rate", "inf"],
+        ["--rate", "0"],
+        ["--rate", "-2"],
+        // Periods too long to represent, or too short to tick at
+        ["--rate", "1e-40"],
+        ["--rate", "1e38"],
+        ["--duration", "-1"],
+        ["--duration", "NaN"],
+        ["--duration", "inf"],
+    ] {
+        assert!(parse(&args).is_err(), "{args:?} was accepted");
+    }
+}
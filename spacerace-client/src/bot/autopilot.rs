@@ -0,0 +1,181 @@
+use spacerace_protocol::{MapGeometry, PublicGameState, Ship};
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use super::planner::{centroid, distance, NavGrid, Point};
+use super::{Bot, ShipInput};
+
+/// Grid resolution for route planning, in pixels
+const CELL_SIZE: f32 = 20.0;
+/// How far routes keep from obstacles, a little more than a ship's radius
+const CLEARANCE: f32 = 30.0;
+/// A waypoint counts as reached once the ship is this close to it
+const WAYPOINT_RADIUS: f32 = 40.0;
+/// Ticks between route plans, so the ship recovers from being knocked off course
+const REPLAN_TICKS: u64 = 60;
+/// A ship moving further than this between two ticks has respawned
+const RESPAWN_JUMP: f32 = 150.0;
+/// Fastest the ship aims to fly, in pixels/s
+const MAX_SPEED: f32 = 300.0;
+/// How quickly the target speed drops towards a waypoint, in 1/s
+const APPROACH_GAIN: f32 = 1.5;
+/// Don't bother thrusting for smaller speed corrections, in pixels/s
+const SPEED_TOLERANCE: f32 = 15.0;
+/// Only thrust when facing within this many radians of the wanted direction
+const THRUST_ANGLE: f32 = PI / 3.0;
+/// The server's simulation rate, for turning ticks into seconds
+const TICKS_PER_SECOND: f32 = 60.0;
+
+/// A baseline opponent: plans a route to the finish around the map's obstacles with A*,
+/// then flies it, steering with a PID controller on the ship's heading.
+///
+/// Moving obstacles, gravity and force fields are ignored, so it's no match for a good pilot.
+pub struct Autopilot {
+    grid: Option<NavGrid>,
+    /// Where to head for when there's no route, e.g. before the map arrives
+    finish: Option<Point>,
+    route: VecDeque<Point>,
+    heading: Pid,
+    last_tick: Option<u64>,
+    last_position: Option<Point>,
+    replan_at: u64,
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Self {
+            grid: None,
+            finish: None,
+            route: VecDeque::new(),
+            heading: Pid::new(2.5, 0.1, 0.8),
+            last_tick: None,
+            last_position: None,
+            replan_at: 0,
+        }
+    }
+}
+
+impl Bot for Autopilot {
+    fn on_game_start(&mut self, map: &MapGeometry) {
+        *self = Self {
+            grid: Some(NavGrid::new(map, CELL_SIZE, CLEARANCE)),
+            finish: map.finish_regions.first().map(centroid),
+            ..Self::default()
+        };
+    }
+
+    fn on_tick(&mut self, state: &PublicGameState, ship: &Ship) -> ShipInput {
+        let position = ship.position;
+        let dt = self
+            .last_tick
+            .map_or(1.0, |tick| state.tick.saturating_sub(tick).max(1) as f32)
+            / TICKS_PER_SECOND;
+        let respawned = self
+            .last_position
+            .is_some_and(|last| distance(last, position) > RESPAWN_JUMP);
+        self.last_tick = Some(state.tick);
+        self.last_position = Some(position);
+
+        if self.route.is_empty() || respawned || state.tick >= self.replan_at {
+            self.replan(position, state.tick);
+        }
+        while self.route.len() > 1 && distance(position, self.route[0]) < WAYPOINT_RADIUS {
+            self.route.pop_front();
+        }
+        let Some(target) = self.route.front().copied().or(self.finish) else {
+            return ShipInput::default();
+        };
+
+        // Fly at a speed the ship can shed before the next waypoint, full speed at the finish
+        let to_target = (target.0 - position.0, target.1 - position.1);
+        let remaining = distance(position, target);
+        let speed = if self.route.len() > 1 {
+            (remaining * APPROACH_GAIN).min(MAX_SPEED)
+        } else {
+            MAX_SPEED
+        };
+        let direction = if remaining > 0.0 {
+            (to_target.0 / remaining, to_target.1 / remaining)
+        } else {
+            (0.0, 0.0)
+        };
+
+        // Thrust to correct the difference between the velocity we want and the one we have
+        let correction = (
+            direction.0 * speed - ship.velocity.0,
+            direction.1 * speed - ship.velocity.1,
+        );
+        if correction.0.hypot(correction.1) < SPEED_TOLERANCE {
+            self.heading.reset();
+            return ShipInput {
+                thrust: 0.0,
+                rotation: (-ship.angular_velocity).clamp(-1.0, 1.0),
+            };
+        }
+
+        let error = angle_difference(heading_towards(correction), ship.orientation);
+        let rotation = self.heading.update(error, ship.angular_velocity, dt);
+        let thrust = if error.abs() < THRUST_ANGLE {
+            error.cos()
+        } else {
+            0.0
+        };
+        ShipInput {
+            thrust: thrust.clamp(0.0, 1.0),
+            rotation: rotation.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+impl Autopilot {
+    fn replan(&mut self, position: Point, tick: u64) {
+        self.replan_at = tick + REPLAN_TICKS;
+        self.route = self
+            .grid
+            .as_ref()
+            .and_then(|grid| grid.plan(position))
+            .unwrap_or_default()
+            .into();
+    }
+}
+
+/// A PID controller driving an angle towards zero error, damped by the measured rate of
+/// change rather than the change in error so a moving target doesn't kick the output.
+struct Pid {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    integral: f32,
+}
+
+impl Pid {
+    fn new(kp: f32, ki: f32, kd: f32) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            integral: 0.0,
+        }
+    }
+
+    fn update(&mut self, error: f32, rate: f32, dt: f32) -> f32 {
+        // Limit the integral so it can't wind up while the output is saturated
+        self.integral = (self.integral + error * dt).clamp(-1.0, 1.0);
+        self.kp * error + self.ki * self.integral - self.kd * rate
+    }
+
+    fn reset(&mut self) {
+        self.integral = 0.0;
+    }
+}
+
+/// The orientation of a ship facing along `direction`. Ships face along their y axis, so an
+/// orientation of zero points up and positive orientations turn anticlockwise.
+fn heading_towards(direction: Point) -> f32 {
+    (-direction.0).atan2(direction.1)
+}
+
+/// The signed angle to turn from `from` to `to`, in [-π, π].
+fn angle_difference(to: f32, from: f32) -> f32 {
+    (to - from + PI).rem_euclid(2.0 * PI) - PI
+}
@@ -0,0 +1,65 @@
+//! Automated players.
+//!
+//! Implement [`Bot`] to decide on the ship's input each tick, then hand it to a [`BotRunner`]
+//! which registers with the lobby, waits for the game and sends the bot's input to the server.
+
+mod autopilot;
+pub(crate) mod planner;
+mod runner;
+
+pub use autopilot::Autopilot;
+pub use runner::BotRunner;
+
+use spacerace_protocol::{MapGeometry, PublicGameState, Ship};
+use uuid::Uuid;
+
+/// Thrust and rotation for a ship, each from -1 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ShipInput {
+    /// -1 for full reverse to 1 for full forward thrust
+    pub thrust: f32,
+    /// -1 for full right (clockwise) to 1 for full left rotational thrust
+    pub rotation: f32,
+}
+
+/// How a race went for the bot.
+#[derive(Clone, Debug)]
+pub struct RaceResults {
+    pub game_id: Uuid,
+    /// Race time in seconds including penalties, `None` when the bot didn't finish
+    pub finish_time: Option<f32>,
+    /// 1 for the winner, `None` when the bot didn't finish
+    pub place: Option<usize>,
+    /// The last state of the game seen, `None` if it ended before any was seen
+    pub state: Option<PublicGameState>,
+}
+
+impl RaceResults {
+    pub(crate) fn new(game_id: Uuid, ship_id: Uuid, state: Option<PublicGameState>) -> Self {
+        let finish_times = state.as_ref().map(|state| &state.finish_times);
+        let finish_time = finish_times.and_then(|times| times.get(&ship_id).copied());
+        let place = finish_time.map(|time| {
+            let faster = finish_times.unwrap().values().filter(|&&t| t < time);
+            faster.count() + 1
+        });
+        Self {
+            game_id,
+            finish_time,
+            place,
+            state,
+        }
+    }
+}
+
+/// An automated player, driven by a [`BotRunner`].
+pub trait Bot {
+    /// Called when the bot's game starts, with the map it is raced on.
+    fn on_game_start(&mut self, _map: &MapGeometry) {}
+
+    /// Called with each new state of the running game while the bot's ship is racing,
+    /// returns the input for the ship.
+    fn on_tick(&mut self, state: &PublicGameState, ship: &Ship) -> ShipInput;
+
+    /// Called once the game is over.
+    fn on_finish(&mut self, _results: &RaceResults) {}
+}
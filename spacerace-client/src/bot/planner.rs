@@ -0,0 +1,257 @@
+use spacerace_protocol::{MapGeometry, MapObject};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// A point in game coordinates.
+pub type Point = (f32, f32);
+
+/// A grid over the map marking where a ship can fly, for planning routes to the finish.
+pub struct NavGrid {
+    /// Bottom left corner of the grid
+    origin: Point,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    blocked: Vec<bool>,
+    goals: Vec<usize>,
+}
+
+impl NavGrid {
+    /// Cells closer than `clearance` to an obstacle or the edge of the map are blocked.
+    pub fn new(map: &MapGeometry, cell_size: f32, clearance: f32) -> Self {
+        let (width, height) = map.size;
+        let columns = (width / cell_size).ceil().max(1.0) as usize;
+        let rows = (height / cell_size).ceil().max(1.0) as usize;
+        let mut grid = Self {
+            origin: (-width / 2.0, -height / 2.0),
+            cell_size,
+            columns,
+            rows,
+            blocked: vec![false; columns * rows],
+            goals: vec![],
+        };
+
+        let solid: Vec<&MapObject> = map.obstacles.iter().filter(|o| !o.sensor).collect();
+        for cell in 0..grid.blocked.len() {
+            let (x, y) = grid.centre(cell);
+            let near_edge = x - grid.origin.0 < clearance
+                || grid.origin.0 + width - x < clearance
+                || y - grid.origin.1 < clearance
+                || grid.origin.1 + height - y < clearance;
+            grid.blocked[cell] =
+                near_edge || solid.iter().any(|o| distance_to(o, (x, y)) < clearance);
+        }
+
+        for finish in &map.finish_regions {
+            let inside: Vec<usize> = (0..grid.blocked.len())
+                .filter(|&cell| finish.contains(grid.centre(cell)))
+                .collect();
+            if inside.is_empty() {
+                // Small regions may not cover any cell's centre
+                grid.goals.extend(grid.cell_at(centroid(finish)));
+            } else {
+                grid.goals.extend(inside);
+            }
+        }
+        grid
+    }
+
+    /// The shortest route from `from` to the finish, as waypoints in line of sight of each
+    /// other. `None` when the finish can't be reached.
+    pub fn plan(&self, from: Point) -> Option<Vec<Point>> {
+        let start = self.cell_at(from)?;
+        let cells = self.search(start)?;
+
+        // Pull the route tight, skipping waypoints the ship can fly straight past
+        let mut waypoints = vec![];
+        let mut anchor = from;
+        for (i, &cell) in cells.iter().enumerate().skip(1) {
+            let next = cells.get(i + 1).map(|&c| self.centre(c));
+            match next {
+                Some(next) if self.line_of_sight(anchor, next) => {}
+                _ => {
+                    anchor = self.centre(cell);
+                    waypoints.push(anchor);
+                }
+            }
+        }
+        Some(waypoints)
+    }
+
+    /// Whether a ship can fly straight from `a` to `b` without entering a blocked cell.
+    pub fn line_of_sight(&self, a: Point, b: Point) -> bool {
+        let length = distance(a, b);
+        let steps = (length / (self.cell_size / 2.0)).ceil().max(1.0) as usize;
+        (0..=steps).all(|step| {
+            let t = step as f32 / steps as f32;
+            let point = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+            self.cell_at(point).is_some_and(|cell| !self.blocked[cell])
+        })
+    }
+
+    /// A* over the grid's cells, moving in eight directions.
+    fn search(&self, start: usize) -> Option<Vec<usize>> {
+        let goals: Vec<Point> = self.goals.iter().map(|&cell| self.centre(cell)).collect();
+        let heuristic = |cell: usize| {
+            let point = self.centre(cell);
+            goals
+                .iter()
+                .map(|&goal| distance(point, goal))
+                .fold(f32::MAX, f32::min)
+        };
+
+        let mut cost = vec![f32::MAX; self.blocked.len()];
+        let mut came_from = vec![usize::MAX; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+        cost[start] = 0.0;
+        open.push(Candidate {
+            cell: start,
+            estimate: heuristic(start),
+        });
+
+        while let Some(Candidate { cell, .. }) = open.pop() {
+            if self.goals.contains(&cell) {
+                let mut route = vec![cell];
+                while let Some(&previous) = came_from.get(*route.last().unwrap()) {
+                    if previous == usize::MAX {
+                        break;
+                    }
+                    route.push(previous);
+                }
+                route.reverse();
+                return Some(route);
+            }
+
+            for (neighbour, step) in self.neighbours(cell) {
+                let new_cost = cost[cell] + step;
+                if new_cost < cost[neighbour] {
+                    cost[neighbour] = new_cost;
+                    came_from[neighbour] = cell;
+                    open.push(Candidate {
+                        cell: neighbour,
+                        estimate: new_cost + heuristic(neighbour),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn neighbours(&self, cell: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let (column, row) = ((cell % self.columns) as i64, (cell / self.columns) as i64);
+        let offsets = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        offsets.into_iter().filter_map(move |(dx, dy)| {
+            let (x, y) = (column + dx, row + dy);
+            if x < 0 || y < 0 || x >= self.columns as i64 || y >= self.rows as i64 {
+                return None;
+            }
+            let neighbour = y as usize * self.columns + x as usize;
+            // Don't cut corners past blocked cells
+            let corner_clear = !self.blocked[row as usize * self.columns + x as usize]
+                && !self.blocked[y as usize * self.columns + column as usize];
+            (!self.blocked[neighbour] && corner_clear).then(|| {
+                (
+                    neighbour,
+                    self.cell_size * ((dx * dx + dy * dy) as f32).sqrt(),
+                )
+            })
+        })
+    }
+
+    fn centre(&self, cell: usize) -> Point {
+        let (column, row) = (cell % self.columns, cell / self.columns);
+        (
+            self.origin.0 + (column as f32 + 0.5) * self.cell_size,
+            self.origin.1 + (row as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    fn cell_at(&self, (x, y): Point) -> Option<usize> {
+        let column = ((x - self.origin.0) / self.cell_size).floor();
+        let row = ((y - self.origin.1) / self.cell_size).floor();
+        let in_grid = column >= 0.0
+            && row >= 0.0
+            && (column as usize) < self.columns
+            && (row as usize) < self.rows;
+        in_grid.then(|| row as usize * self.columns + column as usize)
+    }
+}
+
+/// An open cell in the A* search, ordered so the heap pops the lowest estimate first.
+struct Candidate {
+    cell: usize,
+    estimate: f32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+pub fn distance(a: Point, b: Point) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// The average of a polygon's points in game coordinates.
+pub fn centroid(object: &MapObject) -> Point {
+    let mut points: Vec<Point> = object.points().collect();
+    // Skip the closing point so the first point isn't counted twice
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.is_empty() {
+        return object.position;
+    }
+    let n = points.len() as f32;
+    let (x, y) = points
+        .iter()
+        .fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+    (x / n, y / n)
+}
+
+/// Distance from a point to a polygon's outline, zero inside it.
+fn distance_to(object: &MapObject, point: Point) -> f32 {
+    if object.contains(point) {
+        return 0.0;
+    }
+    let points: Vec<Point> = object.points().collect();
+    points
+        .windows(2)
+        .map(|edge| segment_distance(point, edge[0], edge[1]))
+        .fold(f32::MAX, f32::min)
+}
+
+fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    distance(p, (a.0 + t * dx, a.1 + t * dy))
+}
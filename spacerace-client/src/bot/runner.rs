@@ -0,0 +1,124 @@
+use spacerace_protocol::{GameStatus, PlayerRegistration};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::{Bot, RaceResults, ShipInput};
+use crate::{ClientError, Result, SpaceRaceClient};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Races a [`Bot`]: registers it with the lobby, waits for its game, delivers each new
+/// state to it and sends its input to the server. By default it queues up for the next game
+/// once a race is over.
+pub struct BotRunner<B> {
+    client: SpaceRaceClient,
+    registration: PlayerRegistration,
+    bot: B,
+    poll_interval: Duration,
+    requeue: bool,
+}
+
+impl<B: Bot> BotRunner<B> {
+    pub fn new(client: SpaceRaceClient, registration: PlayerRegistration, bot: B) -> Self {
+        Self {
+            client,
+            registration,
+            bot,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            requeue: true,
+        }
+    }
+
+    /// How often to poll the server for the game's state.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Whether to register for another game after each race.
+    pub fn requeue(mut self, requeue: bool) -> Self {
+        self.requeue = requeue;
+        self
+    }
+
+    pub fn bot(&self) -> &B {
+        &self.bot
+    }
+
+    pub fn into_bot(self) -> B {
+        self.bot
+    }
+
+    /// Races game after game, or a single game when re-queueing is off.
+    ///
+    /// When re-queueing, a game that's dropped before it starts is shrugged off and the bot
+    /// registers for another.
+    pub async fn run(&mut self) -> Result<()> {
+        loop {
+            match self.run_game().await {
+                Ok(results) => {
+                    info!(game_id = %results.game_id, place = ?results.place, "Race over")
+                }
+                Err(ClientError::GameNotActive(game_id)) if self.requeue => {
+                    warn!(%game_id, "Game was dropped before it started")
+                }
+                Err(err) => return Err(err),
+            }
+            if !self.requeue {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Registers for the next game and races it.
+    pub async fn run_game(&mut self) -> Result<RaceResults> {
+        let registration = self.client.register(&self.registration).await?;
+        info!(game_id = %registration.game_id, map = registration.map, "Registered with lobby");
+        let ship_id = registration.ship_id.ok_or(ClientError::UnknownShip)?;
+        let game_id = registration.game_id;
+
+        self.client
+            .wait_for_game(game_id, self.poll_interval)
+            .await?;
+        if let Some(map) = self.client.map(game_id).await? {
+            self.bot.on_game_start(&map);
+        }
+
+        let password = self.registration.password.clone();
+        let mut states = self.client.poll_states(game_id, self.poll_interval);
+        let mut last_state = None;
+        let mut last_input: Option<ShipInput> = None;
+        while let Some(state) = states.next().await? {
+            let ship = state.ships.iter().find(|ship| ship.id == ship_id);
+            if let (GameStatus::Running, Some(ship)) = (&state.state, ship) {
+                let input = self.bot.on_tick(&state, ship);
+
+                // The server holds on to the last input, so only send changes
+                if last_input != Some(input) {
+                    match self
+                        .client
+                        .control(&password, input.thrust, input.rotation)
+                        .await
+                    {
+                        Ok(_) => last_input = Some(input),
+                        Err(ClientError::Rejected(status)) => {
+                            warn!(status, "Server rejected the bot's input")
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+
+            let finished = state.state == GameStatus::Finished;
+            last_state = Some(state);
+            if finished {
+                break;
+            }
+        }
+        debug!(%game_id, "Game over");
+
+        let results = RaceResults::new(game_id, ship_id, last_state);
+        self.bot.on_finish(&results);
+        Ok(results)
+    }
+}
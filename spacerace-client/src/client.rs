@@ -0,0 +1,293 @@
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use spacerace_protocol::{
+    CancelSequenceRequest, ControlInput, ControlSequenceRequest, ControlSequenceResponse,
+    GameStatus, LobbyResponse, LobbyStatus, MapGeometry, MapResponse, PlayerRegistration,
+    PublicGameState, RespawnRequest, RespawnResponse, ShipControlResponse, StateResponse,
+};
+use std::time::Duration;
+use tokio::time::{interval, sleep, Interval, MissedTickBehavior};
+use uuid::Uuid;
+
+use crate::error::{ClientError, Result};
+
+/// Fallback to localhost
+pub const DEFAULT_SERVER: &str = "http://localhost:5000";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// A typed client for the Space Race HTTP API.
+///
+/// Requests time out after a few seconds. Requests that are safe to repeat are retried when
+/// the server can't be reached, times out or fails with a 5xx status.
+#[derive(Clone, Debug)]
+pub struct SpaceRaceClient {
+    http: reqwest::Client,
+    server: String,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+/// A place in a pending game, returned by [`SpaceRaceClient::register`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Registration {
+    pub player_id: String,
+    pub game_id: Uuid,
+    /// Name of the map the game will be raced on
+    pub map: String,
+    /// The player's ship in the game state, older servers don't say
+    pub ship_id: Option<Uuid>,
+}
+
+impl SpaceRaceClient {
+    /// A client for the server at `server`, e.g. "http://localhost:5000".
+    pub fn new(server: impl Into<String>) -> Self {
+        Self::with_timeout(server, DEFAULT_TIMEOUT)
+    }
+
+    pub fn with_timeout(server: impl Into<String>, timeout: Duration) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("HTTP client should build with a timeout");
+        Self {
+            http,
+            server: server.into().trim_end_matches('/').to_string(),
+            retries: DEFAULT_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+
+    /// Retries failed requests up to `retries` times, waiting `delay` between attempts.
+    pub fn retries(mut self, retries: u32, delay: Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay;
+        self
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Joins the lobby, returning the game the player will race in.
+    pub async fn register(&self, registration: &PlayerRegistration) -> Result<Registration> {
+        // A retry could register the player twice, so only retry when nothing was sent
+        let response: LobbyResponse = self
+            .request(Method::POST, "/lobby", Some(registration), false)
+            .await?;
+
+        // The lobby puts the reason in place of the game ID when it turns a player down
+        match Uuid::parse_str(&response.game_id) {
+            Ok(game_id) => Ok(Registration {
+                player_id: response.player_id,
+                game_id,
+                map: response.map,
+                ship_id: response.ship_id,
+            }),
+            Err(_) => Err(ClientError::Lobby(response.game_id)),
+        }
+    }
+
+    /// Where a registered game has got to, or `None` from servers too old to say.
+    pub async fn lobby_status(&self, game_id: Uuid) -> Result<Option<LobbyStatus>> {
+        let path = format!("/lobby?game_id={game_id}");
+        match self.request(Method::GET, &path, None::<&()>, true).await {
+            Ok(status) => Ok(Some(status)),
+            Err(ClientError::Http { source, .. })
+                if source.status() == Some(StatusCode::METHOD_NOT_ALLOWED) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The state of whichever game is running on the server.
+    pub async fn state(&self) -> Result<StateResponse> {
+        self.request(Method::GET, "/state", None::<&()>, true).await
+    }
+
+    /// The state of a specific game, or `None` when it isn't the one running.
+    pub async fn game_state(&self, game_id: Uuid) -> Result<Option<PublicGameState>> {
+        let path = format!("/state?game_id={game_id}");
+        match self.request(Method::GET, &path, None::<&()>, true).await? {
+            StateResponse::Active(state) => Ok(Some(state)),
+            StateResponse::Inactive => Ok(None),
+        }
+    }
+
+    /// Polls the game's state every `period`, see [`StatePoller::next`].
+    pub fn poll_states(&self, game_id: Uuid, period: Duration) -> StatePoller {
+        let mut interval = interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        StatePoller {
+            client: self.clone(),
+            game_id,
+            interval,
+            last_seen: None,
+        }
+    }
+
+    /// Waits for the game to become the active one on the server, polling every `period`.
+    ///
+    /// Fails with [`ClientError::GameNotActive`] if the lobby no longer knows the game, e.g.
+    /// because it was raced without us or the server restarted.
+    pub async fn wait_for_game(&self, game_id: Uuid, period: Duration) -> Result<PublicGameState> {
+        loop {
+            if let Some(state) = self.game_state(game_id).await? {
+                return Ok(state);
+            }
+            if self.lobby_status(game_id).await? == Some(LobbyStatus::Unknown) {
+                return Err(ClientError::GameNotActive(game_id));
+            }
+            sleep(period).await;
+        }
+    }
+
+    /// Sets the ship's thrust and rotation, each from -1 to 1.
+    ///
+    /// Returns the input the server applied after clamping.
+    pub async fn control(
+        &self,
+        password: &str,
+        thrust: f32,
+        rotation: f32,
+    ) -> Result<ShipControlResponse> {
+        let input = ControlInput {
+            password: password.to_string(),
+            thrust,
+            rotation,
+        };
+        let response: ShipControlResponse = self
+            .request(Method::POST, "/control", Some(&input), true)
+            .await?;
+        check_status(&response.status)?;
+        Ok(response)
+    }
+
+    /// Queues inputs for the ship by simulation tick.
+    pub async fn control_sequence(
+        &self,
+        request: &ControlSequenceRequest,
+    ) -> Result<ControlSequenceResponse> {
+        // Appending twice would queue the steps twice
+        let response: ControlSequenceResponse = self
+            .request(
+                Method::POST,
+                "/control/sequence",
+                Some(request),
+                !request.append,
+            )
+            .await?;
+        check_status(&response.status)?;
+        Ok(response)
+    }
+
+    /// Drops the ship's queued control sequence.
+    pub async fn cancel_sequence(&self, password: &str) -> Result<ControlSequenceResponse> {
+        let request = CancelSequenceRequest {
+            password: password.to_string(),
+        };
+        let response: ControlSequenceResponse = self
+            .request(Method::DELETE, "/control/sequence", Some(&request), true)
+            .await?;
+        check_status(&response.status)?;
+        Ok(response)
+    }
+
+    /// Puts the ship back at its last checkpoint, adding a penalty to its finish time.
+    pub async fn respawn(&self, password: &str) -> Result<RespawnResponse> {
+        let request = RespawnRequest {
+            password: password.to_string(),
+        };
+        // Each respawn adds a penalty, so don't risk asking twice
+        let response: RespawnResponse = self
+            .request(Method::POST, "/respawn", Some(&request), false)
+            .await?;
+        check_status(&response.status)?;
+        Ok(response)
+    }
+
+    /// The geometry of the game's map, or `None` when it isn't the game running.
+    pub async fn map(&self, game_id: Uuid) -> Result<Option<MapGeometry>> {
+        let path = format!("/map?game_id={game_id}");
+        match self.request(Method::GET, &path, None::<&()>, true).await? {
+            MapResponse::Active(map) => Ok(Some(map)),
+            MapResponse::Inactive => Ok(None),
+        }
+    }
+
+    async fn request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        idempotent: bool,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.server, path);
+        let mut attempt = 0;
+        loop {
+            let mut request = self.http.request(method.clone(), &url);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let result =
+                async { request.send().await?.error_for_status()?.json::<T>().await }.await;
+
+            match result {
+                Ok(response) => return Ok(response),
+                Err(err) if attempt < self.retries && is_retryable(&err, idempotent) => {
+                    attempt += 1;
+                    tracing::debug!(%url, attempt, "Retrying request: {err}");
+                    sleep(self.retry_delay).await;
+                }
+                Err(source) => return Err(ClientError::Http { url, source }),
+            }
+        }
+    }
+}
+
+/// Delivers a game's state as it changes, see [`SpaceRaceClient::poll_states`].
+pub struct StatePoller {
+    client: SpaceRaceClient,
+    game_id: Uuid,
+    interval: Interval,
+    last_seen: Option<(u64, GameStatus)>,
+}
+
+impl StatePoller {
+    /// Waits for the next state of the game, skipping polls where neither the simulation tick
+    /// nor the game's status has moved on.
+    ///
+    /// Returns `None` once the game is no longer the one running on the server.
+    pub async fn next(&mut self) -> Result<Option<PublicGameState>> {
+        loop {
+            self.interval.tick().await;
+            let Some(state) = self.client.game_state(self.game_id).await? else {
+                return Ok(None);
+            };
+            let seen = Some((state.tick, state.state.clone()));
+            if self.last_seen != seen {
+                self.last_seen = seen;
+                return Ok(Some(state));
+            }
+        }
+    }
+}
+
+fn check_status(status: &str) -> Result<()> {
+    match status {
+        "ok" => Ok(()),
+        status => Err(ClientError::Rejected(status.to_string())),
+    }
+}
+
+/// Requests that may have reached the server are only retried when they are safe to repeat.
+fn is_retryable(err: &reqwest::Error, idempotent: bool) -> bool {
+    err.is_connect()
+        || (idempotent
+            && (err.is_timeout() || err.status().is_some_and(|status| status.is_server_error())))
+}
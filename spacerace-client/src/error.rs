@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The request couldn't be sent or the response couldn't be read, after any retries
+    #[error("request to {url} failed: {source}")]
+    Http {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// The lobby turned the registration down, e.g. "already_registered" or "unknown_map"
+    #[error("lobby rejected registration: {0}")]
+    Lobby(String),
+    /// The server answered with a status other than "ok", e.g. "error" for a wrong password
+    #[error("server rejected request: {0}")]
+    Rejected(String),
+    /// The lobby didn't say which ship in the game state is the player's
+    #[error("server did not return the player's ship ID")]
+    UnknownShip,
+    /// The game isn't running on the server, it may have finished or not started yet
+    #[error("game {0} is not active")]
+    GameNotActive(uuid::Uuid),
+    /// A saved session couldn't be read or written
+    #[error("session file {}: {source}", path.display())]
+    Session {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
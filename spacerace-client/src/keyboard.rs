@@ -0,0 +1,107 @@
+//! Turns key presses and releases into ship input, for clients running in a terminal.
+//!
+//! Terminals that support crossterm's keyboard enhancement report when keys are released.
+//! Others only send a key's press followed by auto-repeats while it's held, so there a key
+//! counts as released once it hasn't been seen for a while.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::bot::ShipInput;
+
+/// Without release events, how long a key counts as held after it was last seen. Longer than
+/// the usual delay before a held key starts repeating, so holding a key doesn't stutter.
+pub const HOLD_TIMEOUT: Duration = Duration::from_millis(550);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Control {
+    Forward,
+    Back,
+    Left,
+    Right,
+}
+
+/// The controls currently held down.
+///
+/// **Controls:**
+/// - Up Arrow or 'w': forward thrust
+/// - Down Arrow or 's': backward thrust
+/// - Left Arrow or 'a': rotate left
+/// - Right Arrow or 'd': rotate right
+/// - 'q' or Ctrl+C: quit
+///
+/// Thrust and rotation keys combine, e.g. forward and left together turn while thrusting.
+#[derive(Debug)]
+pub struct KeyboardInput {
+    /// When each held control was last pressed or repeated
+    held: HashMap<Control, Instant>,
+    /// Whether the terminal reports key releases
+    reports_releases: bool,
+    quit: bool,
+}
+
+impl KeyboardInput {
+    /// Tracks keys for a terminal that does or doesn't report their release.
+    pub fn new(reports_releases: bool) -> Self {
+        Self {
+            held: HashMap::new(),
+            reports_releases,
+            quit: false,
+        }
+    }
+
+    pub fn reports_releases(&self) -> bool {
+        self.reports_releases
+    }
+
+    /// Notes a key event that arrived at `now`.
+    pub fn handle(&mut self, key: KeyEvent, now: Instant) {
+        let control = match key.code {
+            KeyCode::Up | KeyCode::Char('w') => Control::Forward,
+            KeyCode::Down | KeyCode::Char('s') => Control::Back,
+            KeyCode::Left | KeyCode::Char('a') => Control::Left,
+            KeyCode::Right | KeyCode::Char('d') => Control::Right,
+            KeyCode::Char('q') if key.kind == KeyEventKind::Press => {
+                self.quit = true;
+                return;
+            }
+            // Raw mode turns Ctrl+C into a key press rather than a signal
+            KeyCode::Char('c')
+                if key.kind == KeyEventKind::Press
+                    && key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.quit = true;
+                return;
+            }
+            _ => return,
+        };
+        match key.kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                self.held.insert(control, now);
+            }
+            KeyEventKind::Release => {
+                self.held.remove(&control);
+            }
+        }
+    }
+
+    /// Whether the player asked to quit.
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// The input from the controls held at `now`.
+    pub fn input(&mut self, now: Instant) -> ShipInput {
+        if !self.reports_releases {
+            self.held
+                .retain(|_, seen| now.saturating_duration_since(*seen) < HOLD_TIMEOUT);
+        }
+        let held = |control| f32::from(u8::from(self.held.contains_key(&control)));
+        ShipInput {
+            thrust: held(Control::Forward) - held(Control::Back),
+            // Positive rotation turns anticlockwise
+            rotation: held(Control::Left) - held(Control::Right),
+        }
+    }
+}
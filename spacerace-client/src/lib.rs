@@ -0,0 +1,21 @@
+//! A client library for the Space Race server.
+//!
+//! [`SpaceRaceClient`] wraps the HTTP API with the wire types from `spacerace-protocol`,
+//! re-exported here as [`protocol`]. The terminal client in `main.rs` is built on it, draws
+//! races with [`minimap::Minimap`] and reads controls with [`keyboard::KeyboardInput`].
+//! [`bot::BotRunner`] races automated players with it.
+
+pub mod bot;
+mod client;
+mod error;
+pub mod keyboard;
+pub mod minimap;
+mod session;
+
+pub use client::{Registration, SpaceRaceClient, StatePoller, DEFAULT_SERVER};
+pub use error::{ClientError, Result};
+pub use session::Session;
+pub use spacerace_protocol as protocol;
+
+#[cfg(test)]
+mod tests;
@@ -1,101 +1,29 @@
-use crossterm::event::{self, Event, KeyCode};
-use crossterm::{cursor, execute, terminal};
+use anyhow::anyhow;
+use crossterm::event::{
+    self, Event, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
 use rand::distributions::DistString;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use spacerace_client::bot::{Autopilot, BotRunner, ShipInput};
+use spacerace_client::keyboard::KeyboardInput;
+use spacerace_client::minimap::{Cell, Charset, Minimap, Style};
+use spacerace_client::protocol::{GameStatus, LobbyStatus, PlayerRegistration, PublicGameState};
+use spacerace_client::{ClientError, Registration, Session, SpaceRaceClient, DEFAULT_SERVER};
 use std::env;
-use std::io::{stdout, Write};
-use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{debug, info};
-use tracing_subscriber;
-
-/// Fallback to localhost
-const DEFAULT_HOST: &str = "http://localhost:5000";
-
-/// A request to join the lobby, registering a player.
-#[derive(Debug, Serialize)]
-struct LobbyRequest {
-    /// The player's name, used for identification.
-    name: String,
-    /// The player's team name.
-    team: String,
-    /// The player's password for authentication.
-    password: String,
-}
-
-/// The response from the server upon successful lobby registration.
-#[derive(Debug, Deserialize)]
-struct LobbyResponse {
-    /// The unique ID of the registered player.
-    player_id: String,
-    /// The unique game ID that the player is part of.
-    game_id: String,
-    /// The map name of the current game.
-    map: String,
-}
-
-/// Represents the response for the current game state.
-#[derive(Debug, Deserialize)]
-enum GameStateResponse {
-    /// State when no active game is in progress.
-    Inactive,
-    /// State when a game is active. Contains the detailed state.
-    Active(ActiveGameState),
-}
-
-#[derive(Debug, Deserialize, PartialEq)]
-enum GameStatus {
-    Queued,
-    Running,
-    Finished,
-}
-
-/// Details of the currently active game state.
-#[derive(Debug, Deserialize)]
-struct ActiveGameState {
-    /// Unique ID of the active game.
-    game_id: String,
-    /// Current ship states in the game.
-    ships: Vec<ShipState>,
-    /// Name of the current map.
-    map_name: String,
-    /// Game state ("Active", "Queued", etc.)
-    state: GameStatus,
-}
-
-/// Represents the state of a single ship.
-#[derive(Debug, Deserialize)]
-struct ShipState {
-    /// Unique ID of this ship.
-    id: String,
-    /// X, Y position of the ship in the game world.
-    position: [f32; 2],
-    /// Velocity of the ship in X, Y directions.
-    velocity: [f32; 2],
-    /// Current orientation (rotation) of the ship.
-    orientation: f32,
-    /// Angular velocity (rotational speed) of the ship.
-    angular_velocity: f32,
-}
-
-/// A request to control the ship by applying thrust and rotation.
-#[derive(Debug, Serialize)]
-struct ControlRequest {
-    /// Password for authentication.
-    password: String,
-    /// Thrust command (1 = forward, -1 = backward, 0 = none).
-    thrust: i32,
-    /// Rotation command (positive = rotate right, negative = rotate left, 0 = none).
-    rotation: i32,
-}
-
-/// The server's response to a control request.
-#[derive(Debug, Deserialize)]
-struct ControlResponse {
-    /// Status of the control request. Typically "ok".
-    status: String,
-}
+use std::io::{stdout, Stdout, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Lines below the map for the HUD and the instructions
+const HUD_ROWS: u16 = 2;
+/// How long each frame waits for key events, setting how often the state is fetched
+const FRAME_TIME: Duration = Duration::from_millis(50);
+/// Where our place in a game is kept between runs, unless `--session` says otherwise
+const DEFAULT_SESSION_FILE: &str = ".spacerace-session.json";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -105,253 +33,389 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     // Read configuration from environment variables or use defaults.
-    let host = env::var("SPACERACERS_SERVER").unwrap_or_else(|_| DEFAULT_HOST.to_string());
+    let host = env::var("SPACERACERS_SERVER").unwrap_or_else(|_| DEFAULT_SERVER.to_string());
     let player_name = env::var("PLAYER_NAME").unwrap_or_else(|_| "Player".to_string());
     let player_password = env::var("PLAYER_PASSWORD").unwrap_or_else(|_| {
         rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 16)
     });
     let player_team = env::var("PLAYER_TEAM").unwrap_or_else(|_| player_name.clone()); // Default to use player's name as team name.
 
-    let client = reqwest::Client::new();
-    let lobby_response =
-        register_player(&client, &host, player_name, player_team, &player_password).await?;
+    let client = SpaceRaceClient::new(host);
+    let player = PlayerRegistration {
+        name: player_name,
+        team: Some(player_team),
+        password: player_password,
+        map: None,
+        ship_class: None,
+        ship_contact: None,
+    };
 
-    info!("Registered with lobby: {:?}", lobby_response);
+    let args: Vec<String> = env::args().collect();
+
+    // `--requeue` registers for another game after each race
+    let requeue = args.iter().any(|arg| arg == "--requeue");
+
+    // `--bot <name>` races one of the built-in bots instead of taking keyboard input
+    if let Some(index) = args.iter().position(|arg| arg == "--bot") {
+        return match args.get(index + 1).map(String::as_str) {
+            Some("autopilot") => Ok(BotRunner::new(client, player, Autopilot::default())
+                .requeue(requeue)
+                .run()
+                .await?),
+            other => Err(anyhow!(
+                "unknown bot {other:?}, the built-in bots are: autopilot"
+            )),
+        };
+    }
 
-    // Set up terminal for keyboard input
-    crossterm::terminal::enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, cursor::Hide)?;
+    // `--ascii` draws the map without braille, for terminals whose fonts lack it
+    let charset = if args.iter().any(|arg| arg == "--ascii") {
+        Charset::Ascii
+    } else {
+        Charset::Braille
+    };
 
-    loop {
-        // Initial instructions
+    // `--session <path>` says where to keep our place in a game, so a restart can pick it up
+    let session_path = args
+        .iter()
+        .position(|arg| arg == "--session")
+        .and_then(|index| args.get(index + 1))
+        .map_or_else(|| PathBuf::from(DEFAULT_SESSION_FILE), PathBuf::from);
+
+    // Set up terminal for keyboard input, asking for key releases where the terminal can
+    // report them
+    crossterm::terminal::enable_raw_mode()?;
+    execute!(stdout(), cursor::Hide)?;
+    let keyboard_enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+    if keyboard_enhanced {
         execute!(
-            stdout,
-            terminal::Clear(terminal::ClearType::All),
-            cursor::MoveTo(0, 0)
+            stdout(),
+            PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            )
         )?;
+    }
+    let mut keyboard = KeyboardInput::new(keyboard_enhanced);
+
+    let result = race(
+        &client,
+        &player,
+        &session_path,
+        requeue,
+        charset,
+        &mut keyboard,
+    )
+    .await;
+    cleanup_terminal(keyboard_enhanced)?;
+    result
+}
 
-        stdout.flush()?;
+/// Races our game, or game after game when `requeue` is set.
+///
+/// Our place in the game is saved to `session_path` until the race is over, and a game we
+/// were in before a restart is picked up again if it's still on.
+async fn race(
+    client: &SpaceRaceClient,
+    player: &PlayerRegistration,
+    session_path: &Path,
+    requeue: bool,
+    charset: Charset,
+    keyboard: &mut KeyboardInput,
+) -> anyhow::Result<()> {
+    let mut saved = match Session::load(session_path)? {
+        Some(session) if session.name == player.name && session.is_live(client).await? => {
+            info!(game_id = %session.registration.game_id, "Resuming saved session");
+            Some(session)
+        }
+        _ => None,
+    };
 
-        let state_response = get_server_state(&client, &host).await?;
+    loop {
+        let session = match saved.take() {
+            Some(session) => session,
+            None => {
+                let registration = client.register(player).await?;
+                info!("Registered with lobby: {:?}", registration);
+                let session = Session {
+                    server: client.server().to_string(),
+                    name: player.name.clone(),
+                    password: player.password.clone(),
+                    registration,
+                };
+                session.save(session_path)?;
+                session
+            }
+        };
 
-        match state_response {
-            GameStateResponse::Inactive => {
-                println!("Waiting for next game to start.");
+        match wait_for_our_game(client, &session.registration, keyboard).await {
+            Ok(()) => {
+                let (password, registration) = (&session.password, &session.registration);
+                run_game_loop(client, password, registration, charset, keyboard).await?
             }
-            GameStateResponse::Active(active) => {
-                // Check if the active game is the one we have joined
-                if active.game_id == lobby_response.game_id {
-                    // Great, *our* game is active.
-                    println!(
-                        "Our game ({}) is now active. Starting game loop...",
-                        active.game_id
-                    );
-
-                    run_game_loop(client.clone(), player_password.clone(), host.clone()).await?;
-
-                    // If `run_game_loop` returns, that means the game ended or the user quit.
-                    // Break out of the outer loop to exit, or re-register for another game, etc.
-                    println!("Game loop ended. Exiting outer loop...");
-                    break;
-                } else {
-                    // Another game is active, but not ours. We keep waiting, hopefully we haven't missed our game!
-                    println!(
-                        "Game '{}' is active, but we're in game '{}'. Waiting...",
-                        active.game_id, lobby_response.game_id
-                    );
-                    sleep(Duration::from_secs(1)).await;
-                }
+            Err(err)
+                if requeue && matches!(err.downcast_ref(), Some(ClientError::GameNotActive(_))) =>
+            {
+                warn!("{err}, registering again");
             }
+            Err(err) => return Err(err),
         }
-    }
-    // Run the game loop
-    run_game_loop(client, player_password, host).await?;
+        Session::remove(session_path)?;
 
-    Ok(())
+        if !requeue {
+            return Ok(());
+        }
+    }
 }
 
-async fn register_player(
-    client: &Client,
-    host: &String,
-    player_name: String,
-    player_team: String,
-    player_password: &String,
-) -> anyhow::Result<LobbyResponse> {
-    // Register with the lobby
-    let lobby_req = LobbyRequest {
-        name: player_name.clone(),
-        team: player_team.clone(),
-        password: player_password.clone(),
-    };
+/// Shows where our game is in the lobby until it starts.
+///
+/// Fails with [`ClientError::GameNotActive`] if the lobby no longer knows the game, e.g.
+/// because the server restarted.
+async fn wait_for_our_game(
+    client: &SpaceRaceClient,
+    registration: &Registration,
+    keyboard: &mut KeyboardInput,
+) -> anyhow::Result<()> {
+    let mut stdout = stdout();
+    let game_id = registration.game_id;
+
+    loop {
+        if client.game_state(game_id).await?.is_some() {
+            return Ok(());
+        }
+        let waiting = match client.lobby_status(game_id).await? {
+            Some(LobbyStatus::Unknown) => return Err(ClientError::GameNotActive(game_id).into()),
+            Some(LobbyStatus::Queued { ahead, players }) => format!(
+                "Game {game_id} on {} has {players} players and {ahead} games ahead of it. Waiting...",
+                registration.map
+            ),
+            // Either it's only just started, or the server is too old to say
+            Some(LobbyStatus::Active) | None => format!("Waiting for game {game_id} to start..."),
+        };
+
+        queue!(
+            stdout,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print(waiting),
+            cursor::MoveTo(0, 1),
+            Print("Press 'q' to quit.")
+        )?;
+        stdout.flush()?;
 
-    let lobby_response: LobbyResponse = client
-        .post(format!("{}/lobby", host))
-        .json(&lobby_req)
-        .send()
-        .await?
-        .error_for_status()?
-        .json()
-        .await?;
-
-    info!("Registered with lobby: {:?}", lobby_response);
-    Ok(lobby_response)
+        // Doubles as the wait between polls, and lets the player quit
+        read_keys(keyboard, Duration::from_secs(1))?;
+    }
 }
 
 /// Run the main game loop:
 /// - Periodically fetch the current game state from the server.
-/// - Display instructions.
-/// - Poll for keyboard input to send control commands.
-/// - Sleep briefly to avoid overwhelming the server and CPU.
+/// - Draw the map, the ships and a HUD for our own ship.
+/// - Read key presses and releases between frames, sending the input whenever it changes.
+///
+/// Returns once the game has finished.
 ///
 /// # Arguments
 ///
-/// * `client` - The HTTP client for sending requests.
+/// * `client` - The client for the game server.
 /// * `player_password` - The password of the current player.
-/// * `host` - The server host URL.
+/// * `registration` - Our place in the game, to find the map and our ship.
+/// * `charset` - Characters to draw the map with.
+/// * `keyboard` - The keys the player is holding down.
 async fn run_game_loop(
-    client: reqwest::Client,
-    player_password: String,
-    server_url: String,
+    client: &SpaceRaceClient,
+    player_password: &str,
+    registration: &Registration,
+    charset: Charset,
+    keyboard: &mut KeyboardInput,
 ) -> anyhow::Result<()> {
     let mut stdout = stdout();
+    let map = client.map(registration.game_id).await?;
+    let mut minimap: Option<(Minimap, (u16, u16))> = None;
+    let mut started: Option<Instant> = None;
+    let mut sent: Option<ShipInput> = None;
 
     loop {
-        let state_response = get_server_state(&client, &server_url).await?;
+        let Some(active) = client.game_state(registration.game_id).await? else {
+            debug!("Game is no longer active");
+            return Ok(());
+        };
+        if active.state == GameStatus::Finished {
+            debug!("Game finished!");
+            return Ok(());
+        }
+
+        // Game is active or queued, allow user to control. The server holds on to the last
+        // input, so only send changes, including letting go of every key.
+        read_keys(keyboard, FRAME_TIME)?;
+        let input = keyboard.input(Instant::now());
+        if sent != Some(input) {
+            let control_resp = client
+                .control(player_password, input.thrust, input.rotation)
+                .await?;
+            sent = Some(input);
+
+            debug!("Control Response: {:?}", control_resp);
+            if (control_resp.thrust, control_resp.rotation) != (input.thrust, input.rotation) {
+                warn!(
+                    "Server applied thrust {} and rotation {} instead",
+                    control_resp.thrust, control_resp.rotation
+                );
+            }
+        }
 
         // Clear the screen each loop and move cursor to top-left
-        execute!(
+        queue!(
             stdout,
             terminal::Clear(terminal::ClearType::All),
             cursor::MoveTo(0, 0)
         )?;
 
-        // Determine thrust and rotation based on user input
-        let thrust_rotation = if let GameStateResponse::Active(active) = &state_response {
-            if active.state == GameStatus::Finished {
-                debug!("Game finished!");
-                return Ok(());
-            } else {
-                // Game is active or queued, allow user to control
-                thrust_rotation_from_input_with_timeout(Duration::from_millis(200))?
+        // Draw the game, rescaling the map whenever the terminal is resized
+        if active.state == GameStatus::Running {
+            started.get_or_insert_with(Instant::now);
+        }
+        let size = terminal::size()?;
+        if let Some(map) = &map {
+            if minimap.as_ref().is_none_or(|(_, drawn)| *drawn != size) {
+                let (columns, rows) = size;
+                let rows = rows.saturating_sub(HUD_ROWS);
+                let scaled = Minimap::new(map.clone(), columns.into(), rows.into());
+                minimap = Some((scaled.charset(charset), size));
             }
-        } else {
-            debug!("No active game. Waiting for next game to start.");
-            None
-        };
-
-        // If user provided control input, send it to server
-        if let Some((thrust, rotation)) = thrust_rotation {
-            let control_req = ControlRequest {
-                password: player_password.clone(),
-                thrust,
-                rotation,
-            };
-
-            let control_resp: ControlResponse = client
-                .post(format!("{}/control", server_url))
-                .json(&control_req)
-                .send()
-                .await?
-                .error_for_status()?
-                .json()
-                .await?;
-
-            debug!("Control Response: {:?}", control_resp);
         }
-
-        // Output the game state
-        if let GameStateResponse::Active(active) = &state_response {
-            for ship in &active.ships {
-                println!(
-                    "Ship ID: {}, Position: ({:.3}, {:.3}), Velocity: ({:.3}, {:.3}), Orientation: {:.3}",
-                    ship.id,
-                    ship.position[0],
-                    ship.position[1],
-                    ship.velocity[0],
-                    ship.velocity[1],
-                    ship.orientation
-                );
+        let mut row = 0;
+        if let Some((minimap, _)) = &minimap {
+            for cells in minimap.render(&active, registration.ship_id) {
+                queue!(stdout, cursor::MoveTo(0, row))?;
+                draw_cells(&mut stdout, &cells)?;
+                row += 1;
             }
         }
+        let hud = hud(
+            &active,
+            registration.ship_id,
+            minimap.as_ref().map(|(minimap, _)| minimap),
+            started.map(|started| started.elapsed()),
+        );
 
         // Reprint instructions so they stay visible
-        println!("Use arrow keys (or WASD) to control the ship. Press 'q' to quit.");
+        queue!(
+            stdout,
+            cursor::MoveTo(0, row),
+            Print(hud),
+            cursor::MoveTo(0, row + 1),
+            Print("Use arrow keys (or WASD) to control the ship. Press 'q' to quit.")
+        )?;
 
         stdout.flush()?;
-
-        // Throttle updates slightly to avoid high CPU usage
-        sleep(Duration::from_millis(30)).await;
     }
 }
 
-async fn get_server_state(client: &Client, host: &String) -> anyhow::Result<GameStateResponse> {
-    // Get current game state
-    let state_response = client
-        .get(format!("{}/state", host))
-        .send()
-        .await?
-        .error_for_status()?
-        .json::<GameStateResponse>()
-        .await?;
-
-    Ok(state_response)
+/// Prints a row of the minimap, colouring each cell by what it shows.
+fn draw_cells(stdout: &mut Stdout, cells: &[Cell]) -> anyhow::Result<()> {
+    for run in cells.chunk_by(|a, b| a.style == b.style) {
+        let color = match run[0].style {
+            Style::Empty => Color::Reset,
+            Style::Start => Color::Blue,
+            Style::Checkpoint => Color::Yellow,
+            Style::Finish => Color::Green,
+            Style::Obstacle => Color::Grey,
+            Style::Ship => Color::Cyan,
+            Style::OwnShip => Color::Red,
+        };
+        let text: String = run.iter().map(|cell| cell.glyph).collect();
+        if run[0].style == Style::OwnShip {
+            queue!(stdout, SetAttribute(Attribute::Bold))?;
+        }
+        queue!(
+            stdout,
+            SetForegroundColor(color),
+            Print(text),
+            SetAttribute(Attribute::Reset),
+            ResetColor
+        )?;
+    }
+    Ok(())
 }
 
-/// Polls for keyboard input within the given timeout and returns thrust/rotation commands.
-///
-/// **Controls:**
-/// - Up Arrow or 'w': forward thrust (thrust = 15, rotation = 0)
-/// - Down Arrow or 's': backward thrust (thrust = -15, rotation = 0)
-/// - Left Arrow or 'a': rotate left (thrust = 0, rotation = 15)
-/// - Right Arrow or 'd': rotate right (thrust = 0, rotation = -15)
-/// - 'q': quit the application
-///
-/// If no input is provided within the timeout, returns `None`.
-///
-/// # Arguments
-///
-/// * `timeout` - Duration to wait for input before timing out.
-fn thrust_rotation_from_input_with_timeout(
-    timeout: Duration,
-) -> anyhow::Result<Option<(i32, i32)>> {
-    let mut thrust = 0;
-    let mut rotation = 0;
-
-    // Poll once for events within the given timeout.
-    if event::poll(timeout)? {
-        // Keep reading events that have arrived simultaneously
-        while event::poll(Duration::from_millis(0))? {
-            if let Event::Key(key_event) = event::read()? {
-                match key_event.code {
-                    KeyCode::Up | KeyCode::Char('w') => thrust = 15,
-                    KeyCode::Down | KeyCode::Char('s') => thrust = -15,
-                    KeyCode::Left | KeyCode::Char('a') => rotation = 15,
-                    KeyCode::Right | KeyCode::Char('d') => rotation = -15,
-                    KeyCode::Char('q') => {
-                        cleanup_terminal()?;
-                        std::process::exit(0);
-                    }
-                    _ => {}
-                }
-            }
+/// One line about our own ship: speed, race time and position, plus health and fuel.
+fn hud(
+    state: &PublicGameState,
+    ship_id: Option<Uuid>,
+    minimap: Option<&Minimap>,
+    race_time: Option<Duration>,
+) -> String {
+    let no_ship = || {
+        format!(
+            "Game {} is {:?}, we have no ship in it",
+            state.game_id, state.state
+        )
+    };
+    let Some(ship_id) = ship_id else {
+        return no_ship();
+    };
+    // The server takes ships out of the state once they finish or are knocked out
+    let finish_time = state.finish_times.get(&ship_id);
+    let knocked_out = state.did_not_finish.contains(&ship_id);
+    let ship = state.ships.iter().find(|ship| ship.id == ship_id);
+    if ship.is_none() && finish_time.is_none() && !knocked_out {
+        return no_ship();
+    }
+
+    let mut hud = Vec::new();
+    if let Some(ship) = ship {
+        hud.push(format!(
+            "Speed {:>5.0} px/s",
+            ship.velocity.0.hypot(ship.velocity.1)
+        ));
+    }
+    hud.push(match (finish_time, race_time) {
+        (Some(time), _) => format!("Finished in {time:.2}s"),
+        _ if knocked_out => "Did not finish".to_string(),
+        (None, Some(time)) => format!("Time {:>6.1}s", time.as_secs_f32()),
+        (None, None) => "Waiting for the start".to_string(),
+    });
+    if let Some((place, ships)) = minimap.and_then(|minimap| minimap.race_position(state, ship_id))
+    {
+        hud.push(format!("Position {place}/{ships}"));
+    }
+    if let Some(ship) = ship {
+        hud.push(format!("Health {:.0}", ship.health));
+        if let Some(fuel) = ship.fuel {
+            hud.push(format!("Fuel {fuel:.0}"));
         }
+    }
+    hud.join(" | ")
+}
 
-        // If we captured any thrust/rotation inputs, return them.
-        if thrust != 0 || rotation != 0 {
-            return Ok(Some((thrust, rotation)));
-        } else {
-            return Ok(None);
+/// Reads key events into `keyboard` for `timeout`, quitting the application if the player
+/// asks to.
+fn read_keys(keyboard: &mut KeyboardInput, timeout: Duration) -> anyhow::Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if !event::poll(remaining)? {
+            return Ok(());
+        }
+        if let Event::Key(key_event) = event::read()? {
+            keyboard.handle(key_event, Instant::now());
+        }
+        if keyboard.quit() {
+            cleanup_terminal(keyboard.reports_releases())?;
+            std::process::exit(0);
         }
-    } else {
-        Ok(None)
     }
 }
 
 /// Cleans up the terminal by disabling raw mode and showing the cursor again.
 ///
 /// Call this before exiting to restore the user's terminal state to normal.
-fn cleanup_terminal() -> anyhow::Result<()> {
+fn cleanup_terminal(keyboard_enhanced: bool) -> anyhow::Result<()> {
+    if keyboard_enhanced {
+        execute!(std::io::stdout(), PopKeyboardEnhancementFlags)?;
+    }
     crossterm::terminal::disable_raw_mode()?;
     execute!(std::io::stdout(), cursor::Show)?;
     Ok(())
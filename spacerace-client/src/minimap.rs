@@ -0,0 +1,300 @@
+//! Draws a race as text, for clients running in a terminal.
+//!
+//! [`Minimap`] scales a map to fit a block of character cells and draws obstacles, start,
+//! checkpoint and finish regions and ships into it. Each cell holds a 2x4 grid of Unicode
+//! braille dots, so shapes come out at a finer resolution than the cells themselves. The
+//! output is plain [`Cell`]s, colouring them in is left to the caller.
+
+use spacerace_protocol::{MapGeometry, MapObject, PublicGameState};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use uuid::Uuid;
+
+use crate::bot::planner::{centroid, distance, Point};
+
+/// Braille dots per cell, across and down
+const DOTS_ACROSS: usize = 2;
+const DOTS_DOWN: usize = 4;
+/// The braille bit for each dot, indexed by row then column
+const BRAILLE_BITS: [[u32; DOTS_ACROSS]; DOTS_DOWN] =
+    [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+/// Ship glyphs for headings from up, turning anticlockwise
+const BRAILLE_ARROWS: [char; 8] = ['↑', '↖', '←', '↙', '↓', '↘', '→', '↗'];
+const ASCII_ARROWS: [char; 4] = ['^', '<', 'v', '>'];
+
+/// What a cell shows, in increasing order of precedence when several things share a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Style {
+    #[default]
+    Empty,
+    Start,
+    Checkpoint,
+    Finish,
+    Obstacle,
+    Ship,
+    /// The ship being flown by this client
+    OwnShip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub style: Style,
+}
+
+/// Characters to draw with.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Charset {
+    /// Braille dots for the map and arrows in eight directions for ships
+    #[default]
+    Braille,
+    /// One character per cell and arrows in four directions, for terminals without braille
+    Ascii,
+}
+
+/// A map scaled to fit a block of terminal cells.
+pub struct Minimap {
+    map: MapGeometry,
+    charset: Charset,
+    columns: usize,
+    rows: usize,
+    /// Dots per pixel
+    scale: f32,
+    /// Start, checkpoint and finish regions, which never move, as a style per dot
+    regions: Vec<Style>,
+}
+
+impl Minimap {
+    /// Scales `map` to fit in `columns` by `rows` cells, keeping its aspect ratio.
+    pub fn new(map: MapGeometry, columns: usize, rows: usize) -> Self {
+        let (width, height) = map.size;
+        let scale = ((columns * DOTS_ACROSS) as f32 / width)
+            .min((rows * DOTS_DOWN) as f32 / height)
+            .max(0.0);
+        let mut minimap = Self {
+            charset: Charset::default(),
+            columns: ((width * scale).ceil() as usize).div_ceil(DOTS_ACROSS),
+            rows: ((height * scale).ceil() as usize).div_ceil(DOTS_DOWN),
+            scale,
+            regions: Vec::new(),
+            map,
+        };
+
+        let mut regions = vec![Style::Empty; minimap.dots_across() * minimap.dots_down()];
+        for (objects, style) in [
+            (&minimap.map.start_regions, Style::Start),
+            (&minimap.map.checkpoints, Style::Checkpoint),
+            (&minimap.map.finish_regions, Style::Finish),
+        ] {
+            for object in objects {
+                minimap.fill(&mut regions, object, style);
+            }
+        }
+        minimap.regions = regions;
+        minimap
+    }
+
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// The size of the drawing in cells, at most the size asked for.
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Draws the map with its moving obstacles and ships where `state` puts them, as rows
+    /// of cells from the top of the map down.
+    pub fn render(&self, state: &PublicGameState, own_ship: Option<Uuid>) -> Vec<Vec<Cell>> {
+        let mut dots = self.regions.clone();
+        for (index, obstacle) in self.map.obstacles.iter().enumerate() {
+            match state.obstacles.iter().find(|moving| moving.index == index) {
+                Some(pose) => {
+                    let (sin, cos) = pose.rotation.sin_cos();
+                    let moved = MapObject {
+                        position: pose.position,
+                        polygon: obstacle
+                            .polygon
+                            .iter()
+                            .map(|&(x, y)| (x * cos - y * sin, x * sin + y * cos))
+                            .collect(),
+                        sensor: obstacle.sensor,
+                    };
+                    self.fill(&mut dots, &moved, Style::Obstacle);
+                }
+                None => self.fill(&mut dots, obstacle, Style::Obstacle),
+            }
+        }
+
+        let mut cells: Vec<Vec<Cell>> = (0..self.rows)
+            .map(|row| {
+                (0..self.columns)
+                    .map(|column| self.cell(&dots, column, row))
+                    .collect()
+            })
+            .collect();
+
+        // Our own ship goes last so it stays visible in a crowd
+        let mut ships: Vec<_> = state.ships.iter().collect();
+        ships.sort_by_key(|ship| Some(ship.id) == own_ship);
+        for ship in ships {
+            let Some((column, row)) = self
+                .dot(ship.position)
+                .map(|(x, y)| (x / DOTS_ACROSS, y / DOTS_DOWN))
+            else {
+                continue;
+            };
+            cells[row][column] = Cell {
+                glyph: self.arrow(ship.orientation),
+                style: if Some(ship.id) == own_ship {
+                    Style::OwnShip
+                } else {
+                    Style::Ship
+                },
+            };
+        }
+        cells
+    }
+
+    /// A ship's place in the race and the number of ships in it, or `None` if it isn't in
+    /// the game.
+    ///
+    /// Finished ships are ranked by race time. Ships still racing come after them, ranked by
+    /// their distance from the finish as the crow flies, and knocked out ships come last.
+    /// The server drops ships from `ships` once they finish or are knocked out, so those are
+    /// counted from `finish_times` and `did_not_finish`.
+    pub fn race_position(&self, state: &PublicGameState, ship_id: Uuid) -> Option<(usize, usize)> {
+        let finishes: Vec<Point> = self.map.finish_regions.iter().map(centroid).collect();
+        let mut ranks: HashMap<Uuid, (u8, f32)> = HashMap::new();
+        for ship in &state.ships {
+            let remaining = finishes
+                .iter()
+                .map(|&finish| distance(ship.position, finish))
+                .fold(f32::INFINITY, f32::min);
+            ranks.insert(ship.id, (1, remaining));
+        }
+        for id in &state.did_not_finish {
+            ranks.insert(*id, (2, 0.0));
+        }
+        for (id, time) in &state.finish_times {
+            ranks.insert(*id, (0, *time));
+        }
+
+        let ours = *ranks.get(&ship_id)?;
+        let ahead = ranks
+            .iter()
+            .filter(|&(id, rank)| *id != ship_id && *rank < ours)
+            .count();
+        Some((ahead + 1, ranks.len()))
+    }
+
+    fn dots_across(&self) -> usize {
+        self.columns * DOTS_ACROSS
+    }
+
+    fn dots_down(&self) -> usize {
+        self.rows * DOTS_DOWN
+    }
+
+    /// The dot a point in game coordinates falls on, if it's on the map.
+    fn dot(&self, (x, y): Point) -> Option<(usize, usize)> {
+        let (width, height) = self.map.size;
+        let across = ((x + width / 2.0) * self.scale).floor();
+        let down = ((height / 2.0 - y) * self.scale).floor();
+        let on_map = (0.0..self.dots_across() as f32).contains(&across)
+            && (0.0..self.dots_down() as f32).contains(&down);
+        on_map.then_some((across as usize, down as usize))
+    }
+
+    /// The centre of a dot in game coordinates.
+    fn point(&self, across: usize, down: usize) -> Point {
+        let (width, height) = self.map.size;
+        (
+            (across as f32 + 0.5) / self.scale - width / 2.0,
+            height / 2.0 - (down as f32 + 0.5) / self.scale,
+        )
+    }
+
+    /// Sets the dots inside `object` to `style`, unless they already show something that
+    /// takes precedence.
+    fn fill(&self, dots: &mut [Style], object: &MapObject, style: Style) {
+        let (width, height) = self.map.size;
+        let clamp = |(x, y): Point| {
+            (
+                x.clamp(-width / 2.0, width / 2.0),
+                y.clamp(-height / 2.0, height / 2.0),
+            )
+        };
+        // Only test the dots within the polygon's bounding box
+        let (mut min, mut max) = (
+            (f32::INFINITY, f32::INFINITY),
+            (f32::NEG_INFINITY, f32::NEG_INFINITY),
+        );
+        for (x, y) in object.points() {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        if dots.is_empty() || min.0 > max.0 {
+            return;
+        }
+        let last = (self.dots_across() - 1, self.dots_down() - 1);
+        let to_dot = |point: Point| {
+            let (x, y) = clamp(point);
+            (
+                (((x + width / 2.0) * self.scale) as usize).min(last.0),
+                (((height / 2.0 - y) * self.scale) as usize).min(last.1),
+            )
+        };
+        let (left, top) = to_dot((min.0, max.1));
+        let (right, bottom) = to_dot((max.0, min.1));
+
+        for down in top..=bottom {
+            for across in left..=right {
+                let dot = &mut dots[down * self.dots_across() + across];
+                if *dot < style && object.contains(self.point(across, down)) {
+                    *dot = style;
+                }
+            }
+        }
+    }
+
+    fn cell(&self, dots: &[Style], column: usize, row: usize) -> Cell {
+        let mut bits = 0;
+        let mut style = Style::Empty;
+        for (down, bit_row) in BRAILLE_BITS.iter().enumerate() {
+            for (across, bit) in bit_row.iter().enumerate() {
+                let dot = dots
+                    [(row * DOTS_DOWN + down) * self.dots_across() + column * DOTS_ACROSS + across];
+                if dot != Style::Empty {
+                    bits |= bit;
+                    style = style.max(dot);
+                }
+            }
+        }
+        let glyph = match self.charset {
+            Charset::Braille if bits == 0 => ' ',
+            Charset::Braille => char::from_u32(0x2800 + bits).unwrap_or(' '),
+            Charset::Ascii => match style {
+                Style::Empty => ' ',
+                Style::Start => '.',
+                Style::Checkpoint => '+',
+                Style::Finish => '=',
+                _ => '#',
+            },
+        };
+        Cell { glyph, style }
+    }
+
+    /// The arrow closest to a ship's heading. Ships face along their y axis, so an
+    /// orientation of zero points up and positive orientations turn anticlockwise.
+    fn arrow(&self, orientation: f32) -> char {
+        let arrows: &[char] = match self.charset {
+            Charset::Braille => &BRAILLE_ARROWS,
+            Charset::Ascii => &ASCII_ARROWS,
+        };
+        let step = 2.0 * PI / arrows.len() as f32;
+        let index = (orientation / step).round().rem_euclid(arrows.len() as f32) as usize;
+        arrows[index % arrows.len()]
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::{ClientError, Registration, Result, SpaceRaceClient};
+use spacerace_protocol::LobbyStatus;
+
+/// A player's place in a game, saved so a restarted client can pick up where it left off.
+///
+/// The file holds the player's password, so it's only readable by its owner.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// The server the player registered with
+    pub server: String,
+    pub name: String,
+    pub password: String,
+    pub registration: Registration,
+}
+
+impl Session {
+    /// Reads a saved session, `None` if there isn't one.
+    pub fn load(path: &Path) -> Result<Option<Session>> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|err| session_error(path, err.into())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(session_error(path, err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_vec_pretty(self).map_err(|err| session_error(path, err.into()))?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(path)
+            .and_then(|mut file| file.write_all(&json))
+            .map_err(|err| session_error(path, err))
+    }
+
+    /// Deletes a saved session, if there is one.
+    pub fn remove(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(session_error(path, err)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the session's game is still waiting in the lobby or being raced, so its ship
+    /// can still be flown.
+    ///
+    /// Servers too old to say are given the benefit of the doubt.
+    pub async fn is_live(&self, client: &SpaceRaceClient) -> Result<bool> {
+        if self.server != client.server() {
+            return Ok(false);
+        }
+        let status = client.lobby_status(self.registration.game_id).await?;
+        Ok(status != Some(LobbyStatus::Unknown))
+    }
+}
+
+fn session_error(path: &Path, source: io::Error) -> ClientError {
+    ClientError::Session {
+        path: path.to_path_buf(),
+        source,
+    }
+}
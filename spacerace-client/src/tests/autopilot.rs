@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::bot::planner::NavGrid;
+use crate::bot::{Autopilot, Bot};
+use crate::protocol::{GameStatus, MapGeometry, MapObject, PublicGameState, Ship, ShipContact};
+
+fn rectangle(min: (f32, f32), max: (f32, f32)) -> MapObject {
+    MapObject {
+        position: min,
+        polygon: vec![
+            (0.0, 0.0),
+            (max.0 - min.0, 0.0),
+            (max.0 - min.0, max.1 - min.1),
+            (0.0, max.1 - min.1),
+            (0.0, 0.0),
+        ],
+        sensor: false,
+    }
+}
+
+/// A 1000x600 map split by a wall with a gap at the top, starting on the left and
+/// finishing on the right.
+fn walled_map(gap: bool) -> MapGeometry {
+    let wall_top = if gap { 150.0 } else { 300.0 };
+    MapGeometry {
+        name: "walled".to_string(),
+        size: (1000.0, 600.0),
+        gravity: 0.0,
+        obstacles: vec![rectangle((-20.0, -300.0), (20.0, wall_top))],
+        start_regions: vec![rectangle((-450.0, -250.0), (-350.0, -150.0))],
+        finish_regions: vec![rectangle((350.0, -250.0), (450.0, -150.0))],
+        checkpoints: vec![],
+    }
+}
+
+#[test]
+fn test_planner_routes_around_obstacles() {
+    let map = walled_map(true);
+    let grid = NavGrid::new(&map, 20.0, 30.0);
+
+    let route = grid.plan((-400.0, -200.0)).unwrap();
+
+    // The route goes through the gap and ends in the finish region
+    assert!(route.iter().any(|&(x, y)| x.abs() < 40.0 && y > 150.0));
+    assert!(map.finish_regions[0].contains(*route.last().unwrap()));
+    let mut from = (-400.0, -200.0);
+    for &waypoint in &route {
+        assert!(
+            grid.line_of_sight(from, waypoint),
+            "{from:?} to {waypoint:?}"
+        );
+        from = waypoint;
+    }
+    // Pulled tight rather than a waypoint per grid cell
+    assert!(route.len() < 6, "{route:?}");
+}
+
+#[test]
+fn test_planner_gives_up_without_a_way_through() {
+    let grid = NavGrid::new(&walled_map(false), 20.0, 30.0);
+
+    assert_eq!(grid.plan((-400.0, -200.0)), None);
+}
+
+/// Flies the autopilot round the walled map with simple rigid body physics, a stand-in for
+/// the server's that's good enough to catch steering going the wrong way. It doesn't track
+/// the server's Rapier setup, so it's no regression test for the server's physics.
+#[test]
+fn test_autopilot_flies_to_the_finish() {
+    const DT: f32 = 1.0 / 60.0;
+    let map = walled_map(true);
+    let mut autopilot = Autopilot::default();
+    autopilot.on_game_start(&map);
+
+    let mut ship = Ship {
+        id: Uuid::from_u128(1),
+        position: (-400.0, -200.0),
+        velocity: (0.0, 0.0),
+        orientation: 0.0,
+        angular_velocity: 0.0,
+        class: "balanced".to_string(),
+        health: 100.0,
+        fuel: None,
+    };
+    let mut state = PublicGameState {
+        game_id: Uuid::from_u128(2),
+        tick: 0,
+        ships: vec![],
+        obstacles: vec![],
+        map_name: map.name.clone(),
+        state: GameStatus::Running,
+        finish_times: HashMap::new(),
+        did_not_finish: vec![],
+        ship_contact: ShipContact::Contact,
+    };
+
+    for tick in 1..=60 * 30 {
+        state.tick = tick;
+        let input = autopilot.on_tick(&state, &ship);
+        assert!((-1.0..=1.0).contains(&input.thrust));
+        assert!((-1.0..=1.0).contains(&input.rotation));
+
+        // Ships face along their y axis and positive rotation is anticlockwise
+        let heading = (-ship.orientation.sin(), ship.orientation.cos());
+        ship.angular_velocity += (input.rotation * 12.0 - ship.angular_velocity * 0.5) * DT;
+        ship.orientation += ship.angular_velocity * DT;
+        ship.velocity.0 += (heading.0 * input.thrust * 400.0 - ship.velocity.0 * 0.2) * DT;
+        ship.velocity.1 += (heading.1 * input.thrust * 400.0 - ship.velocity.1 * 0.2) * DT;
+        ship.position.0 += ship.velocity.0 * DT;
+        ship.position.1 += ship.velocity.1 * DT;
+
+        assert!(
+            !map.obstacles[0].contains(ship.position),
+            "crashed into the wall at {:?} on tick {tick}",
+            ship.position
+        );
+        if map.finish_regions[0].contains(ship.position) {
+            return;
+        }
+    }
+    panic!("didn't reach the finish, ended up at {:?}", ship.position);
+}
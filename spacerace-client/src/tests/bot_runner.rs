@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{registration, serve};
+use crate::bot::{Bot, BotRunner, RaceResults, ShipInput};
+use crate::protocol::{
+    GameStatus, MapGeometry, MapResponse, PublicGameState, Ship, ShipContact, StateResponse,
+};
+
+/// Flies straight ahead, noting what the runner told it.
+#[derive(Default)]
+struct RecordingBot {
+    map: Option<String>,
+    ticks: Vec<u64>,
+    results: Option<RaceResults>,
+}
+
+impl Bot for RecordingBot {
+    fn on_game_start(&mut self, map: &MapGeometry) {
+        self.map = Some(map.name.clone());
+    }
+
+    fn on_tick(&mut self, state: &PublicGameState, _ship: &Ship) -> ShipInput {
+        self.ticks.push(state.tick);
+        ShipInput {
+            thrust: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    fn on_finish(&mut self, results: &RaceResults) {
+        self.results = Some(results.clone());
+    }
+}
+
+fn state(game_id: Uuid, ship_id: Uuid, tick: u64, status: GameStatus) -> String {
+    let ship = Ship {
+        id: ship_id,
+        position: (0.0, 0.0),
+        velocity: (0.0, 0.0),
+        orientation: 0.0,
+        angular_velocity: 0.0,
+        class: "balanced".to_string(),
+        health: 100.0,
+        fuel: None,
+    };
+    let finish_times = match status {
+        GameStatus::Finished => HashMap::from([(ship_id, 12.5), (Uuid::from_u128(99), 10.0)]),
+        _ => HashMap::new(),
+    };
+    serde_json::to_string(&StateResponse::Active(PublicGameState {
+        game_id,
+        tick,
+        ships: vec![ship],
+        obstacles: vec![],
+        map_name: "Aga".to_string(),
+        state: status,
+        finish_times,
+        did_not_finish: vec![],
+        ship_contact: ShipContact::Contact,
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_runner_races_a_game() {
+    let (game_id, ship_id) = (Uuid::from_u128(1), Uuid::from_u128(2));
+    let map = MapResponse::Active(MapGeometry {
+        name: "Aga".to_string(),
+        size: (1000.0, 1000.0),
+        gravity: 0.0,
+        obstacles: vec![],
+        start_regions: vec![],
+        finish_regions: vec![],
+        checkpoints: vec![],
+    });
+    let (client, requests) = serve(vec![
+        (
+            200,
+            format!(
+                r#"{{"player_id": "Pilot", "game_id": "{game_id}", "map": "Aga", "ship_id": "{ship_id}"}}"#
+            ),
+        ),
+        (200, state(game_id, ship_id, 0, GameStatus::Running)),
+        (200, serde_json::to_string(&map).unwrap()),
+        (200, state(game_id, ship_id, 1, GameStatus::Running)),
+        (200, r#"{"status": "ok", "thrust": 1.0, "rotation": 0.0}"#.to_string()),
+        // The same input isn't sent twice
+        (200, state(game_id, ship_id, 2, GameStatus::Running)),
+        (200, state(game_id, ship_id, 3, GameStatus::Finished)),
+    ])
+    .await;
+
+    let mut runner = BotRunner::new(client, registration(), RecordingBot::default())
+        .poll_interval(std::time::Duration::from_millis(1))
+        .requeue(false);
+    runner.run().await.unwrap();
+
+    let bot = runner.into_bot();
+    assert_eq!(bot.map.as_deref(), Some("Aga"));
+    assert_eq!(bot.ticks, vec![1, 2]);
+    let results = bot.results.unwrap();
+    assert_eq!(results.finish_time, Some(12.5));
+    assert_eq!(results.place, Some(2));
+
+    let requests = requests.lock().unwrap();
+    let posts: Vec<_> = requests.iter().filter(|r| r.starts_with("POST")).collect();
+    assert_eq!(posts, ["POST /lobby HTTP/1.1", "POST /control HTTP/1.1"]);
+}
@@ -0,0 +1,129 @@
+use uuid::Uuid;
+
+use super::{registration, serve};
+use crate::{ClientError, Registration, Session};
+
+#[tokio::test]
+async fn test_register_returns_the_game() {
+    let game_id = Uuid::from_u128(7);
+    let body = format!(r#"{{"player_id": "Pilot", "game_id": "{game_id}", "map": "Aga"}}"#);
+    let (client, requests) = serve(vec![(200, body)]).await;
+
+    let registration = client.register(&registration()).await.unwrap();
+
+    assert_eq!(registration.game_id, game_id);
+    assert_eq!(registration.map, "Aga");
+    assert_eq!(requests.lock().unwrap()[0], "POST /lobby HTTP/1.1");
+}
+
+#[tokio::test]
+async fn test_register_surfaces_lobby_rejections() {
+    let body = r#"{"player_id": "Pilot", "game_id": "unknown_map", "map": "Nowhere"}"#;
+    let (client, _) = serve(vec![(200, body.to_string())]).await;
+
+    let result = client.register(&registration()).await;
+
+    assert!(matches!(result, Err(ClientError::Lobby(reason)) if reason == "unknown_map"));
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retried() {
+    let (client, requests) = serve(vec![
+        (503, "{}".to_string()),
+        (200, r#""Inactive""#.to_string()),
+    ])
+    .await;
+
+    let state = client.game_state(Uuid::from_u128(1)).await.unwrap();
+
+    assert_eq!(state, None);
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1],
+        format!("GET /state?game_id={} HTTP/1.1", Uuid::from_u128(1))
+    );
+}
+
+#[tokio::test]
+async fn test_rejected_controls_are_errors() {
+    let (client, _) = serve(vec![
+        (
+            200,
+            r#"{"status": "ok", "thrust": 1.0, "rotation": -1.0}"#.to_string(),
+        ),
+        (200, r#"{"status": "error"}"#.to_string()),
+    ])
+    .await;
+
+    let applied = client.control("secret", 5.0, -2.0).await.unwrap();
+    assert_eq!((applied.thrust, applied.rotation), (1.0, -1.0));
+
+    let result = client.control("wrong", 1.0, 0.0).await;
+    assert!(matches!(result, Err(ClientError::Rejected(status)) if status == "error"));
+}
+
+#[tokio::test]
+async fn test_waiting_notices_dropped_games() {
+    let game_id = Uuid::from_u128(3);
+    let (client, requests) = serve(vec![
+        (200, r#""Inactive""#.to_string()),
+        (
+            200,
+            r#"{"status": "queued", "ahead": 1, "players": 2}"#.to_string(),
+        ),
+        (200, r#""Inactive""#.to_string()),
+        (200, r#"{"status": "unknown"}"#.to_string()),
+    ])
+    .await;
+
+    let result = client
+        .wait_for_game(game_id, std::time::Duration::from_millis(1))
+        .await;
+
+    assert!(matches!(result, Err(ClientError::GameNotActive(id)) if id == game_id));
+    assert_eq!(
+        requests.lock().unwrap()[1],
+        format!("GET /lobby?game_id={game_id} HTTP/1.1")
+    );
+}
+
+#[tokio::test]
+async fn test_waiting_on_servers_without_lobby_status() {
+    let (client, _) = serve(vec![(405, String::new())]).await;
+
+    let status = client.lobby_status(Uuid::from_u128(3)).await.unwrap();
+
+    assert_eq!(status, None);
+}
+
+#[test]
+fn test_sessions_are_saved_and_removed() {
+    let path = std::env::temp_dir().join(format!("spacerace-session-{}.json", std::process::id()));
+    let session = Session {
+        server: "http://localhost:5000".to_string(),
+        name: "Pilot".to_string(),
+        password: "secret".to_string(),
+        registration: Registration {
+            player_id: "Pilot".to_string(),
+            game_id: Uuid::from_u128(3),
+            map: "Aga".to_string(),
+            ship_id: Some(Uuid::from_u128(4)),
+        },
+    };
+
+    assert_eq!(Session::load(&path).unwrap(), None);
+    session.save(&path).unwrap();
+    assert_eq!(Session::load(&path).unwrap(), Some(session));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    Session::remove(&path).unwrap();
+    assert_eq!(Session::load(&path).unwrap(), None);
+    // Removing is fine when there's nothing to remove
+    Session::remove(&path).unwrap();
+}
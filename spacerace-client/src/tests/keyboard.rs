@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+use std::time::{Duration, Instant};
+
+use crate::bot::ShipInput;
+use crate::keyboard::{KeyboardInput, HOLD_TIMEOUT};
+
+fn key(code: KeyCode, kind: KeyEventKind) -> KeyEvent {
+    KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind,
+        state: KeyEventState::NONE,
+    }
+}
+
+fn input(thrust: f32, rotation: f32) -> ShipInput {
+    ShipInput { thrust, rotation }
+}
+
+#[test]
+fn test_held_keys_combine_until_released() {
+    let mut keyboard = KeyboardInput::new(true);
+    let now = Instant::now();
+
+    keyboard.handle(key(KeyCode::Up, KeyEventKind::Press), now);
+    keyboard.handle(key(KeyCode::Char('a'), KeyEventKind::Press), now);
+    assert_eq!(keyboard.input(now), input(1.0, 1.0));
+
+    // Held keys stay held however long it's been, until they are released
+    let later = now + Duration::from_secs(5);
+    assert_eq!(keyboard.input(later), input(1.0, 1.0));
+    keyboard.handle(key(KeyCode::Char('a'), KeyEventKind::Release), later);
+    keyboard.handle(key(KeyCode::Down, KeyEventKind::Press), later);
+    assert_eq!(keyboard.input(later), input(0.0, 0.0));
+    keyboard.handle(key(KeyCode::Up, KeyEventKind::Release), later);
+    keyboard.handle(key(KeyCode::Right, KeyEventKind::Repeat), later);
+    assert_eq!(keyboard.input(later), input(-1.0, -1.0));
+    assert!(!keyboard.quit());
+}
+
+#[test]
+fn test_keys_time_out_without_release_events() {
+    let mut keyboard = KeyboardInput::new(false);
+    let now = Instant::now();
+
+    keyboard.handle(key(KeyCode::Char('w'), KeyEventKind::Press), now);
+    assert_eq!(keyboard.input(now + HOLD_TIMEOUT / 2), input(1.0, 0.0));
+
+    // Auto-repeat keeps the key held
+    keyboard.handle(
+        key(KeyCode::Char('w'), KeyEventKind::Press),
+        now + HOLD_TIMEOUT / 2,
+    );
+    assert_eq!(keyboard.input(now + HOLD_TIMEOUT), input(1.0, 0.0));
+    assert_eq!(keyboard.input(now + HOLD_TIMEOUT * 2), input(0.0, 0.0));
+}
+
+#[test]
+fn test_quit_keys() {
+    let mut keyboard = KeyboardInput::new(true);
+    keyboard.handle(
+        key(KeyCode::Char('q'), KeyEventKind::Release),
+        Instant::now(),
+    );
+    assert!(!keyboard.quit());
+
+    let mut ctrl_c = key(KeyCode::Char('c'), KeyEventKind::Press);
+    ctrl_c.modifiers = KeyModifiers::CONTROL;
+    keyboard.handle(ctrl_c, Instant::now());
+    assert!(keyboard.quit());
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use uuid::Uuid;
+
+use crate::minimap::{Cell, Charset, Minimap, Style};
+use crate::protocol::{
+    GameStatus, MapGeometry, MapObject, Obstacle, PublicGameState, Ship, ShipContact,
+};
+
+fn rectangle(min: (f32, f32), max: (f32, f32)) -> MapObject {
+    MapObject {
+        position: min,
+        polygon: vec![
+            (0.0, 0.0),
+            (max.0 - min.0, 0.0),
+            (max.0 - min.0, max.1 - min.1),
+            (0.0, max.1 - min.1),
+            (0.0, 0.0),
+        ],
+        sensor: false,
+    }
+}
+
+/// A 100x40 map with an obstacle in the top left corner and the finish in the bottom right.
+fn corner_map() -> MapGeometry {
+    MapGeometry {
+        name: "corners".to_string(),
+        size: (100.0, 40.0),
+        gravity: 0.0,
+        obstacles: vec![rectangle((-50.0, 10.0), (-30.0, 20.0))],
+        start_regions: vec![rectangle((-50.0, -20.0), (-40.0, -10.0))],
+        finish_regions: vec![rectangle((30.0, -20.0), (50.0, -10.0))],
+        checkpoints: vec![],
+    }
+}
+
+fn ship(id: u128, position: (f32, f32), orientation: f32) -> Ship {
+    Ship {
+        id: Uuid::from_u128(id),
+        position,
+        velocity: (0.0, 0.0),
+        orientation,
+        angular_velocity: 0.0,
+        class: "balanced".to_string(),
+        health: 100.0,
+        fuel: None,
+    }
+}
+
+fn state(ships: Vec<Ship>) -> PublicGameState {
+    PublicGameState {
+        game_id: Uuid::from_u128(100),
+        tick: 0,
+        ships,
+        obstacles: vec![],
+        map_name: "corners".to_string(),
+        state: GameStatus::Running,
+        finish_times: HashMap::new(),
+        did_not_finish: vec![],
+        ship_contact: ShipContact::Contact,
+    }
+}
+
+#[test]
+fn test_minimap_draws_map_and_ships() {
+    // One dot per pixel, so each cell covers 2x4 pixels
+    let minimap = Minimap::new(corner_map(), 60, 10);
+    assert_eq!(minimap.size(), (50, 10));
+
+    let ships = vec![ship(1, (0.0, 0.0), 0.0), ship(2, (-20.0, 0.0), PI / 2.0)];
+    let cells = minimap.render(&state(ships), Some(Uuid::from_u128(1)));
+
+    assert_eq!(cells.len(), 10);
+    assert!(cells.iter().all(|row| row.len() == 50));
+    let full = |style| Cell {
+        glyph: '⣿', style
+    };
+    // Game coordinates have y up, so the obstacle is at the top of the drawing
+    assert_eq!(cells[0][0], full(Style::Obstacle));
+    assert_eq!(cells[0][10].style, Style::Empty);
+    assert_eq!(cells[9][45], full(Style::Finish));
+    assert_eq!(cells[9][0], full(Style::Start));
+    // Our ship faces up, the other has turned a quarter anticlockwise to face left
+    assert_eq!(
+        cells[5][25],
+        Cell {
+            glyph: '↑',
+            style: Style::OwnShip
+        }
+    );
+    assert_eq!(
+        cells[5][15],
+        Cell {
+            glyph: '←',
+            style: Style::Ship
+        }
+    );
+
+    let ascii = Minimap::new(corner_map(), 50, 10).charset(Charset::Ascii);
+    let cells = ascii.render(&state(vec![ship(1, (0.0, 0.0), -PI / 2.0)]), None);
+    assert_eq!(cells[0][0].glyph, '#');
+    assert_eq!(cells[9][45].glyph, '=');
+    assert_eq!(
+        cells[5][25],
+        Cell {
+            glyph: '>',
+            style: Style::Ship
+        }
+    );
+}
+
+#[test]
+fn test_minimap_draws_moving_obstacles_where_they_are() {
+    let minimap = Minimap::new(corner_map(), 50, 10);
+    let mut moved = state(vec![]);
+    moved.obstacles = vec![Obstacle {
+        index: 0,
+        position: (10.0, 0.0),
+        rotation: PI / 2.0,
+    }];
+
+    let cells = minimap.render(&moved, None);
+
+    // Turned a quarter anticlockwise about its position, it now covers x 0..10, y 0..20
+    assert_eq!(cells[0][0].style, Style::Empty);
+    assert_eq!(cells[1][27].style, Style::Obstacle);
+    assert_eq!(cells[1][22].style, Style::Empty);
+}
+
+#[test]
+fn test_race_position_ranks_finishers_then_distance_to_finish() {
+    let minimap = Minimap::new(corner_map(), 50, 10);
+    // Like the server, finished and knocked out ships are only listed by ID
+    let mut race = state(vec![
+        ship(1, (-40.0, 0.0), 0.0),
+        ship(2, (20.0, -10.0), 0.0),
+    ]);
+    race.finish_times.insert(Uuid::from_u128(3), 30.0);
+    race.did_not_finish.push(Uuid::from_u128(4));
+
+    assert_eq!(
+        minimap.race_position(&race, Uuid::from_u128(3)),
+        Some((1, 4))
+    );
+    assert_eq!(
+        minimap.race_position(&race, Uuid::from_u128(2)),
+        Some((2, 4))
+    );
+    assert_eq!(
+        minimap.race_position(&race, Uuid::from_u128(1)),
+        Some((3, 4))
+    );
+    assert_eq!(
+        minimap.race_position(&race, Uuid::from_u128(4)),
+        Some((4, 4))
+    );
+    assert_eq!(minimap.race_position(&race, Uuid::from_u128(9)), None);
+
+    race.ships.retain(|ship| ship.id != Uuid::from_u128(1));
+    race.finish_times.insert(Uuid::from_u128(1), 25.0);
+    assert_eq!(
+        minimap.race_position(&race, Uuid::from_u128(1)),
+        Some((1, 4))
+    );
+}
@@ -0,0 +1,58 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::protocol::PlayerRegistration;
+use crate::SpaceRaceClient;
+
+mod autopilot;
+mod bot_runner;
+mod client;
+mod keyboard;
+mod minimap;
+
+/// Serves the canned `(status, body)` responses in order, one per connection, recording the
+/// request line of each request received.
+pub(crate) async fn serve(
+    responses: Vec<(u16, String)>,
+) -> (SpaceRaceClient, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let received = requests.clone();
+    tokio::spawn(async move {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 64 * 1024];
+            let read = stream.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read]);
+            received
+                .lock()
+                .unwrap()
+                .push(request.lines().next().unwrap_or_default().to_string());
+
+            let response = format!(
+                "HTTP/1.1 {status} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    let client =
+        SpaceRaceClient::new(format!("http://{address}/")).retries(2, Duration::from_millis(1));
+    (client, requests)
+}
+
+pub(crate) fn registration() -> PlayerRegistration {
+    PlayerRegistration {
+        name: "Pilot".to_string(),
+        team: None,
+        password: "secret".to_string(),
+        map: None,
+        ship_class: None,
+        ship_contact: None,
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// Sets the player's ship input, sent to `POST /control`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ControlInput {
+    pub password: String,
+
+    /// Thrust from -1 for full reverse to 1 for full forward thrust, out of range values are clamped
+    pub thrust: f32,
+    /// Rotation from -1 for full right (clockwise) to 1 for full left rotational thrust
+    pub rotation: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShipControlResponse {
+    pub status: String,
+    /// The thrust applied after clamping
+    #[serde(default)]
+    pub thrust: f32,
+    /// The rotation applied after clamping
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+impl ShipControlResponse {
+    pub fn error(status: &str) -> Self {
+        Self {
+            status: status.to_string(),
+            thrust: 0.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+/// Queues inputs for the player's ship by simulation tick, sent to `POST /control/sequence`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ControlSequenceRequest {
+    pub password: String,
+    /// Tick to start the sequence at, or the next tick when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_tick: Option<u64>,
+    /// Add the steps after those already queued instead of replacing them
+    #[serde(default)]
+    pub append: bool,
+    pub steps: Vec<ControlStep>,
+}
+
+/// Holds an input for a number of ticks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ControlStep {
+    pub thrust: f32,
+    pub rotation: f32,
+    pub ticks: u64,
+}
+
+/// Drops the player's queued sequence, sent to `DELETE /control/sequence`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CancelSequenceRequest {
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ControlSequenceResponse {
+    pub status: String,
+    /// The tick the queue starts at
+    #[serde(default)]
+    pub start_tick: u64,
+    /// The first tick after the queue has finished
+    #[serde(default)]
+    pub end_tick: u64,
+}
+
+impl ControlSequenceResponse {
+    pub fn error(status: &str) -> Self {
+        Self {
+            status: status.to_string(),
+            start_tick: 0,
+            end_tick: 0,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! Wire types shared by the Space Race server and its clients.
+//!
+//! Everything the HTTP API sends or receives as JSON lives here, so the server and the
+//! clients can't drift apart.
+
+mod control;
+mod lobby;
+mod map;
+mod respawn;
+mod state;
+
+pub use control::{
+    CancelSequenceRequest, ControlInput, ControlSequenceRequest, ControlSequenceResponse,
+    ControlStep, ShipControlResponse,
+};
+pub use lobby::{LobbyResponse, LobbyStatus, PlayerRegistration};
+pub use map::{MapGeometry, MapObject, MapResponse};
+pub use respawn::{RespawnRequest, RespawnResponse};
+pub use state::{GameStatus, Obstacle, PublicGameState, Ship, ShipContact, StateResponse};
+
+#[cfg(test)]
+mod tests;
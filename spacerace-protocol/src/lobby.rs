@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ShipContact;
+
+/// A request to join the lobby, sent to `POST /lobby`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PlayerRegistration {
+    pub name: String,
+    pub team: Option<String>,
+    pub password: String,
+
+    /// Optionally request a game on a specific map, e.g. "Aga" or "generated-42"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<String>,
+
+    /// Optionally pick a ship class, e.g. "light", "heavy" or "balanced"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_class: Option<String>,
+
+    /// Optionally join a game where ships are ghosts, collide, or collide after a while
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_contact: Option<ShipContact>,
+}
+
+/// The lobby's answer to a registration.
+///
+/// On failure `game_id` holds the reason instead of a UUID: "already_registered",
+/// "unknown_map" or "unknown_ship_class".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LobbyResponse {
+    pub player_id: String,
+    pub game_id: String,
+    pub map: String,
+    /// The ID of the player's ship in `/state`, when registered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_id: Option<Uuid>,
+}
+
+/// Where a registered game has got to, the answer to `GET /lobby?game_id=...`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum LobbyStatus {
+    /// Waiting in the lobby behind `ahead` other games, with `players` registered so far
+    Queued { ahead: usize, players: usize },
+    /// Being raced, or only just finished, see `/state`
+    Active,
+    /// The server doesn't know the game: it has already been raced, or the lobby was lost
+    /// when the server restarted
+    Unknown,
+}
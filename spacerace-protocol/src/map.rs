@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// The answer to `GET /map`.
+///
+/// The server sends its full map, clients usually only need the geometry in [`MapGeometry`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum MapResponse<M = MapGeometry> {
+    Inactive,
+    Active(M),
+}
+
+/// The parts of a map needed to plan a route around it.
+///
+/// Game coordinates have the origin at the centre of the map and y pointing up.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MapGeometry {
+    pub name: String,
+    /// Width and height of the map
+    pub size: (f32, f32),
+    pub gravity: f32,
+    pub obstacles: Vec<MapObject>,
+    pub start_regions: Vec<MapObject>,
+    pub finish_regions: Vec<MapObject>,
+    /// Regions ships respawn at once they have passed through them
+    #[serde(default)]
+    pub checkpoints: Vec<MapObject>,
+}
+
+/// A polygon on the map.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MapObject {
+    pub position: (f32, f32),
+    /// Points relative to `position`
+    pub polygon: Vec<(f32, f32)>,
+    /// Sensors don't block ships, they can fly straight through
+    #[serde(default)]
+    pub sensor: bool,
+}
+
+impl MapObject {
+    /// The polygon's points in game coordinates.
+    pub fn points(&self) -> impl Iterator<Item = (f32, f32)> + '_ {
+        let (x, y) = self.position;
+        self.polygon.iter().map(move |&(px, py)| (x + px, y + py))
+    }
+
+    /// Whether a point in game coordinates lies inside the polygon.
+    pub fn contains(&self, (x, y): (f32, f32)) -> bool {
+        let (x, y) = (x - self.position.0, y - self.position.1);
+
+        // Count the polygon edges crossed by a ray heading right from the point
+        let mut inside = false;
+        for edge in self.polygon.windows(2) {
+            let ((ax, ay), (bx, by)) = (edge[0], edge[1]);
+            if (ay > y) != (by > y) && x < ax + (y - ay) / (by - ay) * (bx - ax) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Asks for the player's ship to be put back at its last checkpoint, sent to `POST /respawn`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RespawnRequest {
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RespawnResponse {
+    pub status: String,
+    /// Seconds added to the player's finish time for this respawn
+    pub penalty: f32,
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The answer to `GET /state`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum StateResponse {
+    Inactive,
+    Active(PublicGameState),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub enum GameStatus {
+    Queued,
+    Running,
+    Finished,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PublicGameState {
+    pub game_id: Uuid,
+    /// The current simulation tick, see `/control/sequence`
+    #[serde(default)]
+    pub tick: u64,
+    pub ships: Vec<Ship>,
+    /// Moving obstacles only, static ones are where the map puts them
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    pub map_name: String,
+    pub state: GameStatus,
+    /// Race times in seconds, including penalties, of the players who have finished
+    #[serde(default)]
+    pub finish_times: HashMap<Uuid, f32>,
+    /// Players knocked out of the race, e.g. by hull damage
+    #[serde(default)]
+    pub did_not_finish: Vec<Uuid>,
+    /// Whether ships collide with each other
+    #[serde(default)]
+    pub ship_contact: ShipContact,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Ship {
+    pub id: Uuid,
+    pub position: (f32, f32),
+    pub velocity: (f32, f32),
+    pub orientation: f32,
+    pub angular_velocity: f32,
+    #[serde(default)]
+    pub class: String,
+    #[serde(default)]
+    pub health: f32,
+    /// Remaining fuel, only set on maps with a fuel budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<f32>,
+}
+
+/// Current pose of a moving obstacle, as published in the game state.
+///
+/// The obstacle's polygon from the map is rotated by `rotation` and placed at `position`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Obstacle {
+    /// Index of the obstacle in the map's obstacle list
+    pub index: usize,
+    pub position: (f32, f32),
+    pub rotation: f32,
+}
+
+/// Whether ships collide with each other or fly through one another like ghosts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ShipContact {
+    /// Ships bump into each other
+    #[default]
+    Contact,
+    /// Ships pass through each other, for time trials
+    Ghost,
+    /// Ships are ghosts until `seconds` into the race, letting the pack spread out first
+    ContactAfter { seconds: f32 },
+}
+
+impl ShipContact {
+    /// Whether ships collide with each other `elapsed` seconds into the race.
+    pub fn ships_collide(&self, elapsed: f32) -> bool {
+        match *self {
+            ShipContact::Contact => true,
+            ShipContact::Ghost => false,
+            ShipContact::ContactAfter { seconds } => elapsed >= seconds,
+        }
+    }
+}
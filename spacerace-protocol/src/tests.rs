@@ -0,0 +1,193 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use uuid::Uuid;
+
+use crate::*;
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+    let json = serde_json::to_string(&value).unwrap();
+    let decoded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, value, "round trip through {json}");
+}
+
+fn ship() -> Ship {
+    Ship {
+        id: Uuid::from_u128(1),
+        position: (120.5, -40.0),
+        velocity: (3.25, 0.0),
+        orientation: 1.5,
+        angular_velocity: -0.25,
+        class: "heavy".to_string(),
+        health: 80.0,
+        fuel: Some(42.0),
+    }
+}
+
+#[test]
+fn lobby_types_round_trip() {
+    round_trip(PlayerRegistration {
+        name: "Pilot".to_string(),
+        team: Some("Team".to_string()),
+        password: "secret".to_string(),
+        map: Some("generated-42".to_string()),
+        ship_class: Some("light".to_string()),
+        ship_contact: Some(ShipContact::ContactAfter { seconds: 5.0 }),
+    });
+    round_trip(PlayerRegistration {
+        name: "Pilot".to_string(),
+        team: None,
+        password: "secret".to_string(),
+        map: None,
+        ship_class: None,
+        ship_contact: None,
+    });
+    round_trip(LobbyResponse {
+        player_id: "Pilot".to_string(),
+        game_id: Uuid::from_u128(2).to_string(),
+        map: "Aga".to_string(),
+        ship_id: Some(Uuid::from_u128(5)),
+    });
+    round_trip(LobbyStatus::Queued {
+        ahead: 1,
+        players: 3,
+    });
+    round_trip(LobbyStatus::Unknown);
+}
+
+#[test]
+fn state_types_round_trip() {
+    round_trip(StateResponse::Inactive);
+    for ship_contact in [
+        ShipContact::Contact,
+        ShipContact::Ghost,
+        ShipContact::ContactAfter { seconds: 2.5 },
+    ] {
+        round_trip(StateResponse::Active(PublicGameState {
+            game_id: Uuid::from_u128(3),
+            tick: 360,
+            ships: vec![
+                ship(),
+                Ship {
+                    fuel: None,
+                    ..ship()
+                },
+            ],
+            obstacles: vec![Obstacle {
+                index: 3,
+                position: (10.0, 20.0),
+                rotation: 0.5,
+            }],
+            map_name: "Aga".to_string(),
+            state: GameStatus::Running,
+            finish_times: HashMap::from([(Uuid::from_u128(1), 42.5)]),
+            did_not_finish: vec![Uuid::from_u128(4)],
+            ship_contact,
+        }));
+    }
+}
+
+#[test]
+fn control_types_round_trip() {
+    round_trip(ControlInput {
+        password: "secret".to_string(),
+        thrust: 1.0,
+        rotation: -0.5,
+    });
+    round_trip(ShipControlResponse {
+        status: "ok".to_string(),
+        thrust: 1.0,
+        rotation: -0.5,
+    });
+    round_trip(ShipControlResponse::error("invalid_input"));
+    round_trip(ControlSequenceRequest {
+        password: "secret".to_string(),
+        start_tick: Some(100),
+        append: true,
+        steps: vec![ControlStep {
+            thrust: 1.0,
+            rotation: 0.0,
+            ticks: 30,
+        }],
+    });
+    round_trip(CancelSequenceRequest {
+        password: "secret".to_string(),
+    });
+    round_trip(ControlSequenceResponse {
+        status: "ok".to_string(),
+        start_tick: 100,
+        end_tick: 130,
+    });
+    round_trip(RespawnRequest {
+        password: "secret".to_string(),
+    });
+    round_trip(RespawnResponse {
+        status: "ok".to_string(),
+        penalty: 5.0,
+    });
+}
+
+#[test]
+fn wire_format_is_stable() {
+    assert_eq!(
+        serde_json::to_string(&StateResponse::Inactive).unwrap(),
+        r#""Inactive""#
+    );
+    assert_eq!(
+        serde_json::to_value(ShipContact::ContactAfter { seconds: 5.0 }).unwrap(),
+        serde_json::json!({ "mode": "contact_after", "seconds": 5.0 })
+    );
+    assert_eq!(
+        serde_json::to_value(LobbyStatus::Queued {
+            ahead: 0,
+            players: 2
+        })
+        .unwrap(),
+        serde_json::json!({ "status": "queued", "ahead": 0, "players": 2 })
+    );
+
+    // Ships are published with tuples for their vectors
+    let ship = ship();
+    let json = serde_json::to_value(&ship).unwrap();
+    assert_eq!(json["position"], serde_json::json!([120.5, -40.0]));
+
+    // Optional fields are left out, and older servers may not send the newer ones
+    let registration: PlayerRegistration =
+        serde_json::from_str(r#"{"name": "Pilot", "team": null, "password": "secret"}"#).unwrap();
+    assert_eq!(registration.map, None);
+    assert!(!serde_json::to_string(&registration)
+        .unwrap()
+        .contains("ship_class"));
+    let response: ShipControlResponse = serde_json::from_str(r#"{"status": "ok"}"#).unwrap();
+    assert_eq!((response.thrust, response.rotation), (0.0, 0.0));
+}
+
+#[test]
+fn map_geometry_round_trips_and_contains_points() {
+    let square = MapObject {
+        position: (100.0, 50.0),
+        polygon: vec![
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (20.0, 20.0),
+            (0.0, 20.0),
+            (0.0, 0.0),
+        ],
+        sensor: false,
+    };
+    assert!(square.contains((110.0, 60.0)));
+    assert!(!square.contains((10.0, 10.0)));
+    assert_eq!(square.points().nth(2), Some((120.0, 70.0)));
+
+    round_trip(MapResponse::<MapGeometry>::Inactive);
+    round_trip(MapResponse::Active(MapGeometry {
+        name: "Aga".to_string(),
+        size: (1600.0, 1200.0),
+        gravity: 0.0,
+        obstacles: vec![square.clone()],
+        start_regions: vec![square.clone()],
+        finish_regions: vec![square.clone()],
+        checkpoints: vec![],
+    }));
+}
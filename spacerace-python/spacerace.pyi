@@ -0,0 +1,104 @@
+from typing import Dict, List, Optional, Tuple
+
+DEFAULT_SERVER: str
+
+Point = Tuple[float, float]
+
+class SpaceRaceError(Exception): ...
+class LobbyError(SpaceRaceError): ...
+class RejectedError(SpaceRaceError): ...
+class GameNotActiveError(SpaceRaceError): ...
+
+class Registration:
+    player_id: str
+    game_id: str
+    map: str
+    ship_id: Optional[str]
+
+class LobbyStatus:
+    status: str
+    ahead: Optional[int]
+    players: Optional[int]
+
+class Ship:
+    id: str
+    position: Point
+    velocity: Point
+    orientation: float
+    angular_velocity: float
+    class_: str
+    health: float
+    fuel: Optional[float]
+
+class Obstacle:
+    index: int
+    position: Point
+    rotation: float
+
+class ShipContact:
+    mode: str
+    seconds: Optional[float]
+    def __init__(self, mode: str, seconds: Optional[float] = None) -> None: ...
+    def ships_collide(self, elapsed: float) -> bool: ...
+
+class GameState:
+    game_id: str
+    tick: int
+    ships: List[Ship]
+    obstacles: List[Obstacle]
+    map_name: str
+    state: str
+    finish_times: Dict[str, float]
+    did_not_finish: List[str]
+    ship_contact: ShipContact
+    def ship(self, id: str) -> Optional[Ship]: ...
+
+class MapObject:
+    position: Point
+    polygon: List[Point]
+    sensor: bool
+    def points(self) -> List[Point]: ...
+    def contains(self, point: Point) -> bool: ...
+
+class MapGeometry:
+    name: str
+    size: Point
+    gravity: float
+    obstacles: List[MapObject]
+    start_regions: List[MapObject]
+    finish_regions: List[MapObject]
+    checkpoints: List[MapObject]
+
+class Client:
+    server: str
+    def __init__(
+        self,
+        server: Optional[str] = None,
+        timeout: float = 5.0,
+        retries: int = 3,
+        retry_delay: float = 0.25,
+    ) -> None: ...
+    def register(
+        self,
+        name: str,
+        password: str,
+        team: Optional[str] = None,
+        map: Optional[str] = None,
+        ship_class: Optional[str] = None,
+        ship_contact: Optional[ShipContact] = None,
+    ) -> Registration: ...
+    def lobby_status(self, game_id: str) -> Optional[LobbyStatus]: ...
+    def state(self) -> Optional[GameState]: ...
+    def game_state(self, game_id: str) -> Optional[GameState]: ...
+    def wait_for_game(self, game_id: str, period: float = 0.5) -> GameState: ...
+    def control(self, password: str, thrust: float, rotation: float) -> Tuple[float, float]: ...
+    def control_sequence(
+        self,
+        password: str,
+        steps: List[Tuple[float, float, int]],
+        start_tick: Optional[int] = None,
+        append: bool = False,
+    ) -> Tuple[int, int]: ...
+    def cancel_sequence(self, password: str) -> None: ...
+    def respawn(self, password: str) -> float: ...
+    def map(self, game_id: str) -> Optional[MapGeometry]: ...
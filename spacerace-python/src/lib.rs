@@ -0,0 +1,275 @@
+//! Python bindings for the Space Race client library.
+//!
+//! `spacerace.Client` wraps [`SpaceRaceClient`] in blocking methods, so Python bots make the
+//! same requests, with the same timeouts, retries and error handling, as Rust ones. Responses
+//! come back as the Python classes in [`types`]. Build the module with `maturin develop`.
+
+mod types;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use spacerace_client::protocol::{
+    self, ControlSequenceRequest, ControlStep, PlayerRegistration, StateResponse,
+};
+use spacerace_client::{ClientError, SpaceRaceClient, DEFAULT_SERVER};
+use std::future::Future;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use types::{
+    GameState, LobbyStatus, MapGeometry, MapObject, Obstacle, Registration, Ship, ShipContact,
+};
+
+#[cfg(test)]
+mod tests;
+
+create_exception!(
+    spacerace,
+    SpaceRaceError,
+    PyException,
+    "A request to the server failed."
+);
+create_exception!(
+    spacerace,
+    LobbyError,
+    SpaceRaceError,
+    "The lobby turned the registration down."
+);
+create_exception!(
+    spacerace,
+    RejectedError,
+    SpaceRaceError,
+    "The server rejected the request, e.g. because of a wrong password."
+);
+create_exception!(
+    spacerace,
+    GameNotActiveError,
+    SpaceRaceError,
+    "The lobby no longer knows the game, so it won't start."
+);
+
+fn to_py_err(err: ClientError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        ClientError::Lobby(_) => LobbyError::new_err(message),
+        ClientError::Rejected(_) => RejectedError::new_err(message),
+        ClientError::GameNotActive(_) => GameNotActiveError::new_err(message),
+        _ => SpaceRaceError::new_err(message),
+    }
+}
+
+fn parse_id(id: &str) -> PyResult<Uuid> {
+    Uuid::parse_str(id).map_err(|err| PyValueError::new_err(format!("invalid ID {id:?}: {err}")))
+}
+
+fn seconds(seconds: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(seconds).map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// A blocking client for the Space Race HTTP API.
+///
+/// Requests time out after `timeout` seconds. Requests that are safe to repeat are retried
+/// up to `retries` times when the server can't be reached, times out or fails with a 5xx
+/// status.
+#[pyclass(frozen, module = "spacerace")]
+struct Client {
+    client: SpaceRaceClient,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Runs a request to completion, letting other Python threads run meanwhile.
+    fn block_on<T: Send>(
+        &self,
+        py: Python<'_>,
+        request: impl Future<Output = spacerace_client::Result<T>> + Send,
+    ) -> PyResult<T> {
+        py.allow_threads(|| self.runtime.block_on(request))
+            .map_err(to_py_err)
+    }
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (server = None, timeout = 5.0, retries = 3, retry_delay = 0.25))]
+    fn new(server: Option<String>, timeout: f64, retries: u32, retry_delay: f64) -> PyResult<Self> {
+        let server = server.unwrap_or_else(|| DEFAULT_SERVER.to_string());
+        let client = SpaceRaceClient::with_timeout(server, seconds(timeout)?)
+            .retries(retries, seconds(retry_delay)?);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self { client, runtime })
+    }
+
+    #[getter]
+    fn server(&self) -> &str {
+        self.client.server()
+    }
+
+    /// Joins a game in the lobby, with a `ShipContact` to ask for a game in that contact mode.
+    ///
+    /// Raises `LobbyError` if the password is already registered or the map or ship class
+    /// doesn't exist.
+    #[pyo3(signature = (name, password, team = None, map = None, ship_class = None, ship_contact = None))]
+    // Each argument is a Python keyword argument
+    #[allow(clippy::too_many_arguments)]
+    fn register(
+        &self,
+        py: Python<'_>,
+        name: String,
+        password: String,
+        team: Option<String>,
+        map: Option<String>,
+        ship_class: Option<String>,
+        ship_contact: Option<ShipContact>,
+    ) -> PyResult<Registration> {
+        let registration = PlayerRegistration {
+            name,
+            team,
+            password,
+            map,
+            ship_class,
+            ship_contact: ship_contact.map(Into::into),
+        };
+        self.block_on(py, self.client.register(&registration))
+            .map(Registration::from)
+    }
+
+    /// Where a registered game is in the lobby, `None` if the server doesn't say.
+    fn lobby_status(&self, py: Python<'_>, game_id: &str) -> PyResult<Option<LobbyStatus>> {
+        let game_id = parse_id(game_id)?;
+        let status = self.block_on(py, self.client.lobby_status(game_id))?;
+        Ok(status.map(LobbyStatus::from))
+    }
+
+    /// The state of whichever game is running on the server, `None` if there isn't one.
+    fn state(&self, py: Python<'_>) -> PyResult<Option<GameState>> {
+        match self.block_on(py, self.client.state())? {
+            StateResponse::Active(state) => Ok(Some(state.into())),
+            StateResponse::Inactive => Ok(None),
+        }
+    }
+
+    /// The state of a specific game, `None` when it isn't the one running.
+    fn game_state(&self, py: Python<'_>, game_id: &str) -> PyResult<Option<GameState>> {
+        let game_id = parse_id(game_id)?;
+        let state = self.block_on(py, self.client.game_state(game_id))?;
+        Ok(state.map(GameState::from))
+    }
+
+    /// Waits for the game to become the active one on the server, polling every `period`
+    /// seconds.
+    ///
+    /// Raises `GameNotActiveError` if the lobby no longer knows the game, e.g. because it was
+    /// raced without us or the server restarted.
+    #[pyo3(signature = (game_id, period = 0.5))]
+    fn wait_for_game(&self, py: Python<'_>, game_id: &str, period: f64) -> PyResult<GameState> {
+        let game_id = parse_id(game_id)?;
+        let period = seconds(period)?;
+        // Polls here rather than in SpaceRaceClient::wait_for_game, so Ctrl+C gets through
+        loop {
+            if let Some(state) = self.block_on(py, self.client.game_state(game_id))? {
+                return Ok(state.into());
+            }
+            let status = self.block_on(py, self.client.lobby_status(game_id))?;
+            if status == Some(protocol::LobbyStatus::Unknown) {
+                return Err(to_py_err(ClientError::GameNotActive(game_id)));
+            }
+            py.allow_threads(|| std::thread::sleep(period));
+            py.check_signals()?;
+        }
+    }
+
+    /// Sets the ship's thrust and rotation, each from -1 to 1.
+    ///
+    /// Returns the thrust and rotation the server applied after clamping.
+    fn control(
+        &self,
+        py: Python<'_>,
+        password: &str,
+        thrust: f32,
+        rotation: f32,
+    ) -> PyResult<(f32, f32)> {
+        let response = self.block_on(py, self.client.control(password, thrust, rotation))?;
+        Ok((response.thrust, response.rotation))
+    }
+
+    /// Queues inputs for the ship by simulation tick, as `(thrust, rotation, ticks)` steps.
+    ///
+    /// The sequence starts at `start_tick`, or the next tick when not set. With `append` the
+    /// steps go after those already queued instead of replacing them. Returns the tick the
+    /// queue starts at and the first tick after it has finished.
+    #[pyo3(signature = (password, steps, start_tick = None, append = false))]
+    fn control_sequence(
+        &self,
+        py: Python<'_>,
+        password: String,
+        steps: Vec<(f32, f32, u64)>,
+        start_tick: Option<u64>,
+        append: bool,
+    ) -> PyResult<(u64, u64)> {
+        let request = ControlSequenceRequest {
+            password,
+            start_tick,
+            append,
+            steps: steps
+                .into_iter()
+                .map(|(thrust, rotation, ticks)| ControlStep {
+                    thrust,
+                    rotation,
+                    ticks,
+                })
+                .collect(),
+        };
+        let response = self.block_on(py, self.client.control_sequence(&request))?;
+        Ok((response.start_tick, response.end_tick))
+    }
+
+    /// Drops the ship's queued control sequence.
+    fn cancel_sequence(&self, py: Python<'_>, password: &str) -> PyResult<()> {
+        self.block_on(py, self.client.cancel_sequence(password))?;
+        Ok(())
+    }
+
+    /// Puts the ship back at its last checkpoint. Returns the seconds added to its finish
+    /// time.
+    fn respawn(&self, py: Python<'_>, password: &str) -> PyResult<f32> {
+        let response = self.block_on(py, self.client.respawn(password))?;
+        Ok(response.penalty)
+    }
+
+    /// The geometry of the game's map, `None` when it isn't the game running.
+    fn map(&self, py: Python<'_>, game_id: &str) -> PyResult<Option<MapGeometry>> {
+        let game_id = parse_id(game_id)?;
+        let map = self.block_on(py, self.client.map(game_id))?;
+        Ok(map.map(MapGeometry::from))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Client(server={:?})", self.client.server())
+    }
+}
+
+#[pymodule]
+fn spacerace(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("DEFAULT_SERVER", DEFAULT_SERVER)?;
+    m.add_class::<Client>()?;
+    m.add_class::<Registration>()?;
+    m.add_class::<LobbyStatus>()?;
+    m.add_class::<GameState>()?;
+    m.add_class::<Ship>()?;
+    m.add_class::<Obstacle>()?;
+    m.add_class::<ShipContact>()?;
+    m.add_class::<MapGeometry>()?;
+    m.add_class::<MapObject>()?;
+    let py = m.py();
+    m.add("SpaceRaceError", py.get_type::<SpaceRaceError>())?;
+    m.add("LobbyError", py.get_type::<LobbyError>())?;
+    m.add("RejectedError", py.get_type::<RejectedError>())?;
+    m.add("GameNotActiveError", py.get_type::<GameNotActiveError>())?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use pyo3::prelude::*;
+use spacerace_client::protocol::{self, GameStatus};
+use spacerace_client::ClientError;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::types::{GameState, LobbyStatus, MapGeometry, ShipContact};
+use crate::{to_py_err, GameNotActiveError, LobbyError, RejectedError, SpaceRaceError};
+
+fn square(position: (f32, f32), size: f32) -> protocol::MapObject {
+    protocol::MapObject {
+        position,
+        polygon: vec![
+            (0.0, 0.0),
+            (size, 0.0),
+            (size, size),
+            (0.0, size),
+            (0.0, 0.0),
+        ],
+        sensor: false,
+    }
+}
+
+fn ship(id: u128) -> protocol::Ship {
+    protocol::Ship {
+        id: Uuid::from_u128(id),
+        position: (10.0, -20.0),
+        velocity: (3.0, 4.0),
+        orientation: 0.5,
+        angular_velocity: 0.0,
+        class: "heavy".to_string(),
+        health: 80.0,
+        fuel: Some(25.0),
+    }
+}
+
+#[test]
+fn test_game_state_converts_ids_and_status() {
+    let state = GameState::from(protocol::PublicGameState {
+        game_id: Uuid::from_u128(9),
+        tick: 1200,
+        ships: vec![ship(1)],
+        obstacles: vec![protocol::Obstacle {
+            index: 2,
+            position: (5.0, 5.0),
+            rotation: 1.0,
+        }],
+        map_name: "Aga".to_string(),
+        state: GameStatus::Running,
+        finish_times: HashMap::from([(Uuid::from_u128(2), 31.5)]),
+        did_not_finish: vec![Uuid::from_u128(3)],
+        ship_contact: protocol::ShipContact::ContactAfter { seconds: 10.0 },
+    });
+
+    assert_eq!(state.game_id, Uuid::from_u128(9).to_string());
+    assert_eq!(state.tick, 1200);
+    assert_eq!(state.state, "Running");
+    assert_eq!(state.obstacles[0].index, 2);
+    assert_eq!(state.finish_times[&Uuid::from_u128(2).to_string()], 31.5);
+    assert_eq!(state.did_not_finish, vec![Uuid::from_u128(3).to_string()]);
+
+    let ship = state.ship(&Uuid::from_u128(1).to_string()).unwrap();
+    assert_eq!(ship.class, "heavy");
+    assert_eq!(ship.velocity, (3.0, 4.0));
+    assert_eq!(ship.fuel, Some(25.0));
+    assert!(state.ship("not-a-ship").is_none());
+
+    assert_eq!(state.ship_contact.mode, "contact_after");
+    assert_eq!(state.ship_contact.seconds, Some(10.0));
+    assert!(!state.ship_contact.ships_collide(5.0));
+    assert!(state.ship_contact.ships_collide(10.0));
+}
+
+#[test]
+fn test_lobby_status_flattens_to_optional_fields() {
+    let queued = LobbyStatus::from(protocol::LobbyStatus::Queued {
+        ahead: 1,
+        players: 3,
+    });
+    assert_eq!(queued.status, "queued");
+    assert_eq!((queued.ahead, queued.players), (Some(1), Some(3)));
+
+    let active = LobbyStatus::from(protocol::LobbyStatus::Active);
+    assert_eq!(active.status, "active");
+    assert_eq!((active.ahead, active.players), (None, None));
+
+    assert_eq!(
+        LobbyStatus::from(protocol::LobbyStatus::Unknown).status,
+        "unknown"
+    );
+}
+
+#[test]
+fn test_map_objects_keep_the_protocol_geometry() {
+    let map = MapGeometry::from(protocol::MapGeometry {
+        name: "squares".to_string(),
+        size: (200.0, 100.0),
+        gravity: 0.0,
+        obstacles: vec![square((-50.0, -10.0), 20.0)],
+        start_regions: vec![square((-100.0, -50.0), 10.0)],
+        finish_regions: vec![square((90.0, 40.0), 10.0)],
+        checkpoints: vec![],
+    });
+
+    let obstacle = &map.obstacles[0];
+    assert!(obstacle.contains((-40.0, 0.0)));
+    assert!(!obstacle.contains((0.0, 0.0)));
+    assert_eq!(obstacle.points()[2], (-30.0, 10.0));
+    assert!(map.finish_regions[0].contains((95.0, 45.0)));
+    assert!(map.checkpoints.is_empty());
+}
+
+#[test]
+fn test_client_errors_raise_matching_exceptions() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let lobby = to_py_err(ClientError::Lobby("unknown_map".to_string()));
+        assert!(lobby.is_instance_of::<LobbyError>(py));
+        // Every exception can be caught as the base class
+        assert!(lobby.is_instance_of::<SpaceRaceError>(py));
+        assert!(lobby.to_string().contains("unknown_map"));
+
+        let rejected = to_py_err(ClientError::Rejected("error".to_string()));
+        assert!(rejected.is_instance_of::<RejectedError>(py));
+
+        let not_active = to_py_err(ClientError::GameNotActive(Uuid::from_u128(1)));
+        assert!(not_active.is_instance_of::<GameNotActiveError>(py));
+
+        let other = to_py_err(ClientError::UnknownShip);
+        assert!(other.is_instance_of::<SpaceRaceError>(py));
+        assert!(!other.is_instance_of::<LobbyError>(py));
+        assert!(!other.is_instance_of::<RejectedError>(py));
+    });
+}
+
+#[test]
+fn test_ship_contact_for_registration() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|_| {
+        let contact_after = ShipContact::new("contact_after", Some(5.0)).unwrap();
+        assert_eq!(contact_after.mode, "contact_after");
+        assert_eq!(
+            protocol::ShipContact::from(contact_after),
+            protocol::ShipContact::ContactAfter { seconds: 5.0 }
+        );
+        assert_eq!(
+            protocol::ShipContact::from(ShipContact::new("ghost", None).unwrap()),
+            protocol::ShipContact::Ghost
+        );
+
+        for (mode, seconds) in [
+            ("contact_after", None),
+            ("contact_after", Some(-1.0)),
+            ("contact_after", Some(f32::NAN)),
+            ("ghost", Some(1.0)),
+            ("bumper_cars", None),
+        ] {
+            assert!(
+                ShipContact::new(mode, seconds).is_err(),
+                "{mode} {seconds:?} was accepted"
+            );
+        }
+    });
+}
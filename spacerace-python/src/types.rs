@@ -0,0 +1,364 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use spacerace_client::protocol;
+use std::collections::HashMap;
+
+/// A place in a pending game, returned by `Client.register`.
+#[pyclass(frozen, get_all, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct Registration {
+    pub player_id: String,
+    pub game_id: String,
+    /// Name of the map the game will be raced on
+    pub map: String,
+    /// The player's ship in the game state, older servers don't say
+    pub ship_id: Option<String>,
+}
+
+#[pymethods]
+impl Registration {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl From<spacerace_client::Registration> for Registration {
+    fn from(registration: spacerace_client::Registration) -> Self {
+        Self {
+            player_id: registration.player_id,
+            game_id: registration.game_id.to_string(),
+            map: registration.map,
+            ship_id: registration.ship_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+/// Where a registered game has got to, see `Client.lobby_status`.
+#[pyclass(frozen, get_all, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct LobbyStatus {
+    /// "queued", "active" or "unknown"
+    pub status: String,
+    /// Games ahead of this one in the lobby, while it's queued
+    pub ahead: Option<usize>,
+    /// Players registered so far, while it's queued
+    pub players: Option<usize>,
+}
+
+#[pymethods]
+impl LobbyStatus {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl From<protocol::LobbyStatus> for LobbyStatus {
+    fn from(status: protocol::LobbyStatus) -> Self {
+        let (status, ahead, players) = match status {
+            protocol::LobbyStatus::Queued { ahead, players } => {
+                ("queued", Some(ahead), Some(players))
+            }
+            protocol::LobbyStatus::Active => ("active", None, None),
+            protocol::LobbyStatus::Unknown => ("unknown", None, None),
+        };
+        Self {
+            status: status.to_string(),
+            ahead,
+            players,
+        }
+    }
+}
+
+/// A ship in the game state.
+///
+/// Game coordinates have the origin at the centre of the map and y pointing up. Ships face
+/// along their y axis, so an orientation of zero points up and positive orientations turn
+/// anticlockwise.
+#[pyclass(frozen, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct Ship {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub position: (f32, f32),
+    #[pyo3(get)]
+    pub velocity: (f32, f32),
+    #[pyo3(get)]
+    pub orientation: f32,
+    #[pyo3(get)]
+    pub angular_velocity: f32,
+    /// `class` is a Python keyword
+    #[pyo3(get, name = "class_")]
+    pub class: String,
+    #[pyo3(get)]
+    pub health: f32,
+    /// Remaining fuel, only set on maps with a fuel budget
+    #[pyo3(get)]
+    pub fuel: Option<f32>,
+}
+
+#[pymethods]
+impl Ship {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl From<protocol::Ship> for Ship {
+    fn from(ship: protocol::Ship) -> Self {
+        Self {
+            id: ship.id.to_string(),
+            position: ship.position,
+            velocity: ship.velocity,
+            orientation: ship.orientation,
+            angular_velocity: ship.angular_velocity,
+            class: ship.class,
+            health: ship.health,
+            fuel: ship.fuel,
+        }
+    }
+}
+
+/// Current pose of a moving obstacle: the map's polygon rotated by `rotation` and placed at
+/// `position`.
+#[pyclass(frozen, get_all, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    /// Index of the obstacle in the map's obstacle list
+    pub index: usize,
+    pub position: (f32, f32),
+    pub rotation: f32,
+}
+
+impl From<protocol::Obstacle> for Obstacle {
+    fn from(obstacle: protocol::Obstacle) -> Self {
+        Self {
+            index: obstacle.index,
+            position: obstacle.position,
+            rotation: obstacle.rotation,
+        }
+    }
+}
+
+/// Whether ships collide with each other or fly through one another like ghosts.
+///
+/// Pass one to `Client.register` to ask for a game with that mode, e.g.
+/// `ShipContact("contact_after", 10.0)`.
+#[pyclass(frozen, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct ShipContact {
+    /// "contact", "ghost" or "contact_after"
+    #[pyo3(get)]
+    pub mode: String,
+    /// Seconds into the race ships start colliding, for "contact_after"
+    #[pyo3(get)]
+    pub seconds: Option<f32>,
+    inner: protocol::ShipContact,
+}
+
+#[pymethods]
+impl ShipContact {
+    #[new]
+    #[pyo3(signature = (mode, seconds = None))]
+    pub fn new(mode: &str, seconds: Option<f32>) -> PyResult<Self> {
+        let inner = match (mode, seconds) {
+            ("contact", None) => protocol::ShipContact::Contact,
+            ("ghost", None) => protocol::ShipContact::Ghost,
+            ("contact_after", Some(seconds)) if seconds.is_finite() && seconds >= 0.0 => {
+                protocol::ShipContact::ContactAfter { seconds }
+            }
+            ("contact_after", _) => {
+                return Err(PyValueError::new_err(
+                    "contact_after needs the seconds until ships collide, 0 or more",
+                ))
+            }
+            ("contact" | "ghost", Some(_)) => {
+                return Err(PyValueError::new_err(format!(
+                    "{mode} doesn't take seconds"
+                )))
+            }
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown ship contact mode {mode:?}, expected contact, ghost or contact_after"
+                )))
+            }
+        };
+        Ok(inner.into())
+    }
+
+    /// Whether ships collide with each other `elapsed` seconds into the race.
+    pub fn ships_collide(&self, elapsed: f32) -> bool {
+        self.inner.ships_collide(elapsed)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ShipContact(mode={:?}, seconds={:?})",
+            self.mode, self.seconds
+        )
+    }
+}
+
+impl From<protocol::ShipContact> for ShipContact {
+    fn from(inner: protocol::ShipContact) -> Self {
+        let (mode, seconds) = match inner {
+            protocol::ShipContact::Contact => ("contact", None),
+            protocol::ShipContact::Ghost => ("ghost", None),
+            protocol::ShipContact::ContactAfter { seconds } => ("contact_after", Some(seconds)),
+        };
+        Self {
+            mode: mode.to_string(),
+            seconds,
+            inner,
+        }
+    }
+}
+
+impl From<ShipContact> for protocol::ShipContact {
+    fn from(contact: ShipContact) -> Self {
+        contact.inner
+    }
+}
+
+/// The state of a game, as published by `/state`.
+#[pyclass(frozen, get_all, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct GameState {
+    pub game_id: String,
+    /// The current simulation tick, see `Client.control_sequence`
+    pub tick: u64,
+    pub ships: Vec<Ship>,
+    /// Moving obstacles only, static ones are where the map puts them
+    pub obstacles: Vec<Obstacle>,
+    pub map_name: String,
+    /// "Queued", "Running" or "Finished"
+    pub state: String,
+    /// Race times in seconds, including penalties, of the players who have finished
+    pub finish_times: HashMap<String, f32>,
+    /// Players knocked out of the race
+    pub did_not_finish: Vec<String>,
+    pub ship_contact: ShipContact,
+}
+
+#[pymethods]
+impl GameState {
+    /// The ship with the given ID, `None` if it isn't in the race (any more).
+    pub fn ship(&self, id: &str) -> Option<Ship> {
+        self.ships.iter().find(|ship| ship.id == id).cloned()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GameState(game_id={:?}, tick={}, state={:?}, ships={})",
+            self.game_id,
+            self.tick,
+            self.state,
+            self.ships.len()
+        )
+    }
+}
+
+impl From<protocol::PublicGameState> for GameState {
+    fn from(state: protocol::PublicGameState) -> Self {
+        let status = match state.state {
+            protocol::GameStatus::Queued => "Queued",
+            protocol::GameStatus::Running => "Running",
+            protocol::GameStatus::Finished => "Finished",
+        };
+        Self {
+            game_id: state.game_id.to_string(),
+            tick: state.tick,
+            ships: state.ships.into_iter().map(Ship::from).collect(),
+            obstacles: state.obstacles.into_iter().map(Obstacle::from).collect(),
+            map_name: state.map_name,
+            state: status.to_string(),
+            finish_times: state
+                .finish_times
+                .into_iter()
+                .map(|(id, time)| (id.to_string(), time))
+                .collect(),
+            did_not_finish: state
+                .did_not_finish
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            ship_contact: state.ship_contact.into(),
+        }
+    }
+}
+
+/// The parts of a map needed to plan a route around it.
+#[pyclass(frozen, get_all, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct MapGeometry {
+    pub name: String,
+    /// Width and height of the map
+    pub size: (f32, f32),
+    pub gravity: f32,
+    pub obstacles: Vec<MapObject>,
+    pub start_regions: Vec<MapObject>,
+    pub finish_regions: Vec<MapObject>,
+    /// Regions ships respawn at once they have passed through them
+    pub checkpoints: Vec<MapObject>,
+}
+
+impl From<protocol::MapGeometry> for MapGeometry {
+    fn from(map: protocol::MapGeometry) -> Self {
+        let objects = |objects: Vec<protocol::MapObject>| {
+            objects
+                .into_iter()
+                .map(|inner| MapObject { inner })
+                .collect()
+        };
+        Self {
+            name: map.name,
+            size: map.size,
+            gravity: map.gravity,
+            obstacles: objects(map.obstacles),
+            start_regions: objects(map.start_regions),
+            finish_regions: objects(map.finish_regions),
+            checkpoints: objects(map.checkpoints),
+        }
+    }
+}
+
+/// A polygon on the map.
+#[pyclass(frozen, module = "spacerace")]
+#[derive(Clone, Debug)]
+pub struct MapObject {
+    inner: protocol::MapObject,
+}
+
+#[pymethods]
+impl MapObject {
+    #[getter]
+    fn position(&self) -> (f32, f32) {
+        self.inner.position
+    }
+
+    /// Points relative to `position`
+    #[getter]
+    fn polygon(&self) -> Vec<(f32, f32)> {
+        self.inner.polygon.clone()
+    }
+
+    /// Sensors don't block ships, they can fly straight through
+    #[getter]
+    fn sensor(&self) -> bool {
+        self.inner.sensor
+    }
+
+    /// The polygon's points in game coordinates.
+    pub fn points(&self) -> Vec<(f32, f32)> {
+        self.inner.points().collect()
+    }
+
+    /// Whether a point in game coordinates lies inside the polygon.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        self.inner.contains(point)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+}
@@ -1,10 +1,12 @@
 use crate::components::Player;
-use crate::control::ShipInput;
+use crate::control::{ScheduledInput, ShipInput};
 use crate::game_state::GameState;
 use crate::game_state::PendingGame;
+use crate::map::generator::MapGenerator;
 use crate::map::NamedMapId;
+use crate::ship_class::ShipClasses;
 use bevy::prelude::Resource;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 
 use uuid::Uuid;
@@ -20,6 +22,11 @@ pub struct AppState {
 
     // Stores current inputs from players
     pub control_inputs: Arc<Mutex<HashMap<Uuid, ShipInput>>>,
+    // Input sequences players have queued up, taking over from their control inputs
+    pub control_sequences: Arc<Mutex<HashMap<Uuid, VecDeque<ScheduledInput>>>>,
+
+    // Ship classes players can choose between
+    pub ship_classes: Arc<Mutex<ShipClasses>>,
 }
 
 impl AppState {
@@ -29,6 +36,8 @@ impl AppState {
             lobby: Arc::new(Mutex::new(Vec::new())),
             active_game: Arc::new(Mutex::new(None)),
             control_inputs: Arc::new(Mutex::new(Default::default())),
+            control_sequences: Arc::new(Mutex::new(Default::default())),
+            ship_classes: Arc::new(Mutex::new(Default::default())),
         }
     }
 
@@ -37,6 +46,15 @@ impl AppState {
         maps.push(id);
     }
 
+    /// Looks up a map by name, which may also describe a generated map such as `generated-42`.
+    pub fn find_map(&self, name: &str) -> Option<NamedMapId> {
+        let maps = self.map_ids.lock().unwrap();
+        maps.iter()
+            .find(|map| map.0 == name)
+            .cloned()
+            .or_else(|| MapGenerator::from_name(name).map(|g| NamedMapId::generated(&g)))
+    }
+
     pub fn get_active_player_by_password(&self, password: &str) -> Option<(GameState, Player)> {
         let active_game = self.active_game.lock().unwrap();
         if let Some(game) = active_game.as_ref() {
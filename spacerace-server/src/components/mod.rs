@@ -1,5 +1,6 @@
 use bevy::prelude::Component;
 
+pub mod obstacle;
 pub mod player;
 pub mod ship;
 
@@ -15,5 +16,30 @@ pub struct Name(pub String);
 #[derive(Component)]
 pub struct FinishRegion;
 
+/// Sensor region ships respawn at after passing through it.
+#[derive(Component)]
+pub struct CheckpointRegion {
+    /// Index of the checkpoint in the map's checkpoint list
+    pub index: usize,
+}
+
+/// Sensor region adding speed to ships crossing it.
+#[derive(Component)]
+pub struct BoostRegion {
+    pub strength: f32,
+    /// Boost along this direction, or along the ship's heading when `None`
+    pub direction: Option<bevy::math::Vec2>,
+}
+
+/// Sensor region adding linear damping to ships inside it.
+#[derive(Component)]
+pub struct SlowRegion {
+    pub damping: f32,
+}
+
 #[derive(Component)]
 pub struct ActiveGameEntity;
+
+/// Scales the damage a ship takes when it hits this entity.
+#[derive(Component)]
+pub struct Damage(pub f32);
@@ -0,0 +1,20 @@
+use bevy::math::Vec2;
+use bevy::prelude::Component;
+
+use crate::map::Motion;
+
+pub use spacerace_protocol::Obstacle;
+
+/// An obstacle animated along its map-defined motion during the race.
+#[derive(Component)]
+pub struct MovingObstacle {
+    /// Index of the obstacle in the map's obstacle list
+    pub index: usize,
+    pub motion: Motion,
+    /// Where the obstacle starts, in game coordinates
+    pub origin: Vec2,
+    /// The point the obstacle rotates about, relative to its position
+    pub centre: Vec2,
+    /// Race time the obstacle has been moving for, advanced a fixed step each tick
+    pub elapsed: f32,
+}
@@ -1,13 +1,7 @@
 use bevy::prelude::Component;
 use serde::{Deserialize, Serialize};
 
-// Player structure
-#[derive(Component, Debug, Serialize, Deserialize, Clone)]
-pub struct PlayerRegistration {
-    pub name: String,
-    pub team: Option<String>,
-    pub password: String,
-}
+pub use spacerace_protocol::PlayerRegistration;
 
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Player {
@@ -16,6 +10,10 @@ pub struct Player {
     pub name: String,
     pub team: Option<String>,
     pub password: String,
+
+    /// The ship class the player flies, or the default class when not set
+    #[serde(default)]
+    pub ship_class: Option<String>,
 }
 
 impl Player {
@@ -25,12 +23,16 @@ impl Player {
             name,
             team,
             password,
+            ship_class: None,
         }
     }
 }
 
 impl From<PlayerRegistration> for Player {
     fn from(registration: PlayerRegistration) -> Self {
-        Self::new(registration.name, registration.team, registration.password)
+        Self {
+            ship_class: registration.ship_class,
+            ..Self::new(registration.name, registration.team, registration.password)
+        }
     }
 }
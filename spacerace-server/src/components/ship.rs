@@ -1,20 +1,27 @@
 use bevy::prelude::Component;
-use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::map::FuelBudget;
+
+pub use spacerace_protocol::Ship;
+
 #[derive(Component)]
 pub struct ControllableShip {
     pub id: Uuid,
+    /// Name of the ship's class
+    pub class: String,
 
     pub impulse: f32,
     pub torque_impulse: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Ship {
-    pub id: Uuid,
-    pub position: (f32, f32),
-    pub velocity: (f32, f32),
-    pub orientation: f32,
-    pub angular_velocity: f32,
+/// Fuel left in a ship's tank, on maps with a fuel budget.
+#[derive(Component)]
+pub struct Fuel {
+    pub remaining: f32,
+    pub budget: FuelBudget,
 }
+
+/// What's left of a ship's hull. The ship is eliminated when it reaches zero.
+#[derive(Component)]
+pub struct Health(pub f32);
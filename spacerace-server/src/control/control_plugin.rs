@@ -1,7 +1,8 @@
 use crate::app_state::AppState;
 use crate::components;
 use crate::components::ship::ControllableShip;
-use crate::components::ship::Ship;
+use crate::components::ship::{Fuel, Health, Ship};
+use crate::control::scheduled_input;
 use crate::game_logic::ServerState;
 use bevy::prelude::*;
 use bevy_rapier2d::dynamics::{ExternalImpulse, Velocity};
@@ -13,7 +14,9 @@ impl Plugin for ControlPlugin {
         app.add_systems(OnExit(ServerState::Active), cleanup_controls);
         app.add_systems(
             Update,
-            apply_controls_system.run_if(in_state(ServerState::Active)),
+            (advance_tick_system, apply_controls_system, refuel_system)
+                .chain()
+                .run_if(in_state(ServerState::Active)),
         );
         app.add_systems(
             PostUpdate,
@@ -26,32 +29,60 @@ fn cleanup_controls(app_state: Res<AppState>) {
     // Remove all control inputs in `AppState.control_inputs` for all players
     let mut control_inputs_lock = app_state.control_inputs.lock().unwrap();
     control_inputs_lock.clear();
+    app_state.control_sequences.lock().unwrap().clear();
+}
+
+/// Counts the frames of the race, which scheduled control inputs are timed by.
+fn advance_tick_system(app_state: Res<AppState>) {
+    if let Some(active_game) = app_state.active_game.lock().unwrap().as_mut() {
+        active_game.tick += 1;
+    }
 }
 
 fn apply_controls_system(
     app_state: Res<AppState>,
+    time: Res<Time>,
     mut player_info: Query<(
         &components::ship::ControllableShip,
         &mut Transform,
         &mut ExternalImpulse,
+        Option<&mut Fuel>,
     )>,
 ) {
-    let mut control_inputs_lock = app_state.control_inputs.lock().unwrap();
+    let tick = match app_state.active_game.lock().unwrap().as_ref() {
+        Some(game) => game.tick,
+        None => return,
+    };
+    let control_inputs_lock = app_state.control_inputs.lock().unwrap();
+    let mut control_sequences_lock = app_state.control_sequences.lock().unwrap();
 
-    // Check `AppState.control_inputs` for each ship
+    // Check `AppState.control_inputs` for each ship, unless a queued sequence is running
     // Apply impulses based on the recorded inputs
-    for (player, mut transform, mut rb_imps) in &mut player_info {
+    for (player, mut transform, mut rb_imps, fuel) in &mut player_info {
         let player_uuid = player.id.clone();
-        let control_input = match control_inputs_lock.get(&player_uuid) {
-            Some(input) => input,
-            None => {
-                tracing::trace!(player.id = ?player.id, "No control input found");
-                continue;
-            }
-        };
+        let scheduled_input = control_sequences_lock
+            .get_mut(&player_uuid)
+            .and_then(|queue| scheduled_input(queue, tick));
+        let control_input =
+            match scheduled_input.or_else(|| control_inputs_lock.get(&player_uuid).cloned()) {
+                Some(input) => input,
+                None => {
+                    tracing::trace!(player.id = ?player.id, "No control input found");
+                    continue;
+                }
+            };
         let thrust = control_input.thrust;
         let rotation = control_input.rotation;
 
+        // Ships out of fuel can't fire their thrusters, they coast
+        if let Some(mut fuel) = fuel {
+            if fuel.remaining <= 0.0 {
+                continue;
+            }
+            let burn = fuel.budget.burn(thrust, rotation, time.delta_secs());
+            fuel.remaining = (fuel.remaining - burn).max(0.0);
+        }
+
         // Thrust exerts an impulse on the rigid body along the axis the ship is facing (not traveling)
         // get the ship's forward vector by applying the current rotation to the ships initial facing
         // vector
@@ -60,16 +91,44 @@ fn apply_controls_system(
         let heading_2d = Vec2::new(heading.x, heading.y);
 
         // Apply an impulse to the rigid body along the ship axis
-        rb_imps.impulse = thrust as f32 * heading_2d * player.impulse;
+        rb_imps.impulse = thrust * heading_2d * player.impulse;
 
         // Apply a torque impulse to the rigid body
-        rb_imps.torque_impulse = rotation as f32 * player.torque_impulse;
+        rb_imps.torque_impulse = rotation * player.torque_impulse;
+    }
+}
+
+/// Tops up the fuel of ships inside refuel zones.
+fn refuel_system(
+    app_state: Res<AppState>,
+    time: Res<Time>,
+    mut query: Query<(&Transform, &mut Fuel)>,
+) {
+    let active_game_lock = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_lock.as_ref() else {
+        return;
+    };
+
+    for (transform, mut fuel) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        for zone in &active_game.map.refuel_zones {
+            if zone.zone.contains(position) {
+                fuel.remaining += zone.rate * time.delta_secs();
+            }
+        }
+        fuel.remaining = fuel.remaining.min(fuel.budget.capacity);
     }
 }
 
 pub fn update_public_game_state_system(
     app_state: Res<AppState>,
-    query: Query<(&ControllableShip, &Transform, &Velocity)>,
+    query: Query<(
+        &ControllableShip,
+        &Transform,
+        &Velocity,
+        &Health,
+        Option<&Fuel>,
+    )>,
 ) {
     let mut active_game_lock = app_state.active_game.lock().unwrap();
     let active_game = match active_game_lock.as_mut() {
@@ -83,7 +142,7 @@ pub fn update_public_game_state_system(
     // Build a new set of ships from the ECS data
     let mut ships: Vec<Ship> = Vec::new();
 
-    for (player, transform, velocity) in query.iter() {
+    for (player, transform, velocity, health, fuel) in query.iter() {
         // Find the player UUID from the game's ship entities.
         let player_uuid = player.id.clone();
         tracing::trace!(player.id = ?player.id, "Getting ship state");
@@ -98,6 +157,9 @@ pub fn update_public_game_state_system(
             velocity: (vel.x, vel.y),
             orientation: transform.rotation.to_euler(EulerRot::XYZ).2,
             angular_velocity: ang_vel,
+            class: player.class.clone(),
+            health: health.0,
+            fuel: fuel.map(|fuel| fuel.remaining),
         };
         tracing::trace!(?ship, "Adding ship to game state");
         ships.push(ship);
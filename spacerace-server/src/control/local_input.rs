@@ -0,0 +1,459 @@
+//! Local players on the server's own display, for couch multiplayer in the `ui` build.
+//!
+//! While the lobby is showing, pressing a keyboard binding's thrust key or a gamepad's join
+//! button registers a local player, and from then on that device flies only that player's
+//! ship. Bindings come from the JSON file `SPACERACERS_INPUT_BINDINGS` points at, if set.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::app_state::AppState;
+use crate::components::PlayerRegistration;
+use crate::control::ShipInput;
+use crate::game_logic::ServerState;
+use crate::network::lobby_route::register_player;
+
+/// Team local players are registered in
+const LOCAL_TEAM: &str = "Local";
+
+pub struct LocalInputPlugin;
+
+impl Plugin for LocalInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>()
+            .init_resource::<LocalPlayers>()
+            .add_systems(Startup, load_input_bindings)
+            .add_systems(
+                Update,
+                join_local_players_system.run_if(in_state(ServerState::Inactive)),
+            )
+            .add_systems(Update, release_local_players_system)
+            .add_systems(
+                Update,
+                apply_local_controls_system.run_if(in_state(ServerState::Active)),
+            );
+    }
+}
+
+/// Controls for local players: any number of keyboard layouts sharing the keyboard, and a
+/// layout every gamepad uses.
+#[derive(Resource, Clone, Debug, PartialEq, Deserialize)]
+pub struct InputBindings {
+    #[serde(default)]
+    pub keyboards: Vec<KeyBindings>,
+    #[serde(default)]
+    pub gamepad: GamepadBindings,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct KeyBindings {
+    /// Name of the local player this layout registers
+    pub name: String,
+    /// Thrusting also joins the lobby
+    pub thrust: Vec<BoundKey>,
+    pub reverse: Vec<BoundKey>,
+    pub left: Vec<BoundKey>,
+    pub right: Vec<BoundKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GamepadBindings {
+    pub join: Vec<BoundButton>,
+    pub thrust: Vec<BoundButton>,
+    pub reverse: Vec<BoundButton>,
+    pub left: Vec<BoundButton>,
+    pub right: Vec<BoundButton>,
+    /// Stick axis steering the ship, pushed right to turn clockwise
+    pub steering: Option<BoundAxis>,
+    /// Stick movements smaller than this are ignored
+    pub dead_zone: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let keys = |keys: &[KeyCode]| keys.iter().copied().map(BoundKey).collect();
+        Self {
+            keyboards: vec![
+                KeyBindings {
+                    name: "WASD".to_string(),
+                    thrust: keys(&[KeyCode::KeyW]),
+                    reverse: keys(&[KeyCode::KeyS]),
+                    left: keys(&[KeyCode::KeyA]),
+                    right: keys(&[KeyCode::KeyD]),
+                },
+                KeyBindings {
+                    name: "Arrows".to_string(),
+                    thrust: keys(&[KeyCode::ArrowUp]),
+                    reverse: keys(&[KeyCode::ArrowDown]),
+                    left: keys(&[KeyCode::ArrowLeft]),
+                    right: keys(&[KeyCode::ArrowRight]),
+                },
+            ],
+            gamepad: GamepadBindings::default(),
+        }
+    }
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        let buttons =
+            |buttons: &[GamepadButton]| buttons.iter().copied().map(BoundButton).collect();
+        Self {
+            join: buttons(&[GamepadButton::South, GamepadButton::Start]),
+            thrust: buttons(&[GamepadButton::RightTrigger2, GamepadButton::South]),
+            reverse: buttons(&[GamepadButton::LeftTrigger2]),
+            left: buttons(&[GamepadButton::DPadLeft]),
+            right: buttons(&[GamepadButton::DPadRight]),
+            steering: Some(BoundAxis(GamepadAxis::LeftStickX)),
+            dead_zone: 0.2,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn input(&self, keyboard: &ButtonInput<KeyCode>) -> ShipInput {
+        let held = |keys: &[BoundKey]| keyboard.any_pressed(keys.iter().map(|key| key.0));
+        ShipInput {
+            thrust: axis(held(&self.thrust), held(&self.reverse)),
+            // Positive rotation turns anticlockwise
+            rotation: axis(held(&self.left), held(&self.right)),
+        }
+    }
+}
+
+impl GamepadBindings {
+    pub fn input(&self, gamepad: &Gamepad) -> ShipInput {
+        let held = |buttons: &[BoundButton]| buttons.iter().any(|button| gamepad.pressed(button.0));
+        let steering = self
+            .steering
+            .and_then(|axis| gamepad.get(axis.0))
+            .filter(|value| value.abs() > self.dead_zone)
+            .unwrap_or(0.0);
+        ShipInput {
+            thrust: axis(held(&self.thrust), held(&self.reverse)),
+            rotation: (axis(held(&self.left), held(&self.right)) - steering).clamp(-1.0, 1.0),
+        }
+    }
+}
+
+/// 1 for the positive direction, -1 for the negative one, 0 for both or neither.
+fn axis(positive: bool, negative: bool) -> f32 {
+    f32::from(u8::from(positive)) - f32::from(u8::from(negative))
+}
+
+/// A device driving a local player's ship.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    /// One of the keyboard layouts, by index
+    Keyboard(usize),
+    Gamepad(Entity),
+}
+
+/// Which device flies which registered player's ship.
+#[derive(Resource, Default, Debug)]
+pub struct LocalPlayers {
+    assignments: Vec<(InputDevice, Uuid)>,
+}
+
+impl LocalPlayers {
+    pub fn player(&self, device: InputDevice) -> Option<Uuid> {
+        self.assignments
+            .iter()
+            .find(|(assigned, _)| *assigned == device)
+            .map(|(_, player_id)| *player_id)
+    }
+
+    pub fn assignments(&self) -> &[(InputDevice, Uuid)] {
+        &self.assignments
+    }
+
+    /// Registers a local player in the lobby and hands them the device, unless it's taken.
+    pub fn join(&mut self, app_state: &AppState, device: InputDevice, name: &str) -> Option<Uuid> {
+        if self.player(device).is_some() {
+            return None;
+        }
+        let response = register_player(
+            app_state,
+            PlayerRegistration {
+                name: name.to_string(),
+                team: Some(LOCAL_TEAM.to_string()),
+                // Local players are never controlled over HTTP
+                password: Uuid::new_v4().to_string(),
+                map: None,
+                ship_class: None,
+                ship_contact: None,
+            },
+        );
+        let Some(player_id) = response.ship_id else {
+            tracing::warn!(
+                name,
+                reason = %response.game_id,
+                "Local player couldn't join"
+            );
+            return None;
+        };
+        tracing::info!(
+            name,
+            ?device,
+            ?player_id,
+            game_id = %response.game_id,
+            "Local player joined"
+        );
+        self.assignments.push((device, player_id));
+        Some(player_id)
+    }
+
+    /// Frees the devices of players who are no longer waiting or racing.
+    pub fn release_finished(&mut self, app_state: &AppState) {
+        // Lock in the same order as the game scheduler
+        let lobby = app_state.lobby.lock().unwrap();
+        let active_game = app_state.active_game.lock().unwrap();
+        let playing = |player_id: &Uuid| {
+            lobby
+                .iter()
+                .flat_map(|game| &game.players)
+                .chain(active_game.iter().flat_map(|game| &game.players))
+                .any(|player| player.id == *player_id)
+        };
+        self.assignments.retain(|(_, player_id)| playing(player_id));
+    }
+}
+
+/// Loads the bindings from `SPACERACERS_INPUT_BINDINGS`, if set.
+pub fn load_input_bindings(mut bindings: ResMut<InputBindings>) {
+    let Ok(path) = std::env::var("SPACERACERS_INPUT_BINDINGS") else {
+        return;
+    };
+
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| Ok(serde_json::from_str::<InputBindings>(&json)?));
+    match loaded {
+        Ok(loaded) => {
+            tracing::info!(
+                ?path,
+                keyboards = loaded.keyboards.len(),
+                "Loaded input bindings"
+            );
+            *bindings = loaded;
+        }
+        Err(err) => tracing::warn!(
+            ?path,
+            ?err,
+            "Failed to load input bindings, using the built-in ones"
+        ),
+    }
+}
+
+/// Registers a local player for each unassigned device whose join input was just pressed.
+fn join_local_players_system(
+    app_state: Res<AppState>,
+    bindings: Res<InputBindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut local_players: ResMut<LocalPlayers>,
+) {
+    for (index, keys) in bindings.keyboards.iter().enumerate() {
+        if keyboard.any_just_pressed(keys.thrust.iter().map(|key| key.0)) {
+            local_players.join(&app_state, InputDevice::Keyboard(index), &keys.name);
+        }
+    }
+
+    for (entity, gamepad) in &gamepads {
+        let joining = bindings
+            .gamepad
+            .join
+            .iter()
+            .any(|button| gamepad.just_pressed(button.0));
+        if joining && local_players.player(InputDevice::Gamepad(entity)).is_none() {
+            let number = local_players
+                .assignments()
+                .iter()
+                .filter(|(device, _)| matches!(device, InputDevice::Gamepad(_)))
+                .count()
+                + 1;
+            let name = format!("Gamepad {number}");
+            local_players.join(&app_state, InputDevice::Gamepad(entity), &name);
+        }
+    }
+}
+
+fn release_local_players_system(app_state: Res<AppState>, mut local_players: ResMut<LocalPlayers>) {
+    local_players.release_finished(&app_state);
+}
+
+/// Feeds each local device's input to its own player's ship, as if it had come over HTTP.
+fn apply_local_controls_system(
+    app_state: Res<AppState>,
+    bindings: Res<InputBindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    local_players: Res<LocalPlayers>,
+) {
+    let mut control_inputs = app_state.control_inputs.lock().unwrap();
+    for &(device, player_id) in local_players.assignments() {
+        let input = match device {
+            InputDevice::Keyboard(index) => bindings
+                .keyboards
+                .get(index)
+                .map(|keys| keys.input(&keyboard)),
+            InputDevice::Gamepad(entity) => gamepads
+                .get(entity)
+                .ok()
+                .map(|gamepad| bindings.gamepad.input(gamepad)),
+        };
+        // A disconnected gamepad lets go of the controls
+        control_inputs.insert(player_id, input.unwrap_or_default());
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct BoundKey(pub KeyCode);
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct BoundButton(pub GamepadButton);
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct BoundAxis(pub GamepadAxis);
+
+impl TryFrom<String> for BoundKey {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        lookup(KEYS, &name).map(BoundKey)
+    }
+}
+
+impl TryFrom<String> for BoundButton {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        lookup(GAMEPAD_BUTTONS, &name).map(BoundButton)
+    }
+}
+
+impl TryFrom<String> for BoundAxis {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        lookup(GAMEPAD_AXES, &name).map(BoundAxis)
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Result<T, String> {
+    table
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| format!("unknown input {name:?}"))
+}
+
+/// Inputs that can be bound, by their Bevy names
+const KEYS: &[(&str, KeyCode)] = &[
+    ("KeyA", KeyCode::KeyA),
+    ("KeyB", KeyCode::KeyB),
+    ("KeyC", KeyCode::KeyC),
+    ("KeyD", KeyCode::KeyD),
+    ("KeyE", KeyCode::KeyE),
+    ("KeyF", KeyCode::KeyF),
+    ("KeyG", KeyCode::KeyG),
+    ("KeyH", KeyCode::KeyH),
+    ("KeyI", KeyCode::KeyI),
+    ("KeyJ", KeyCode::KeyJ),
+    ("KeyK", KeyCode::KeyK),
+    ("KeyL", KeyCode::KeyL),
+    ("KeyM", KeyCode::KeyM),
+    ("KeyN", KeyCode::KeyN),
+    ("KeyO", KeyCode::KeyO),
+    ("KeyP", KeyCode::KeyP),
+    ("KeyQ", KeyCode::KeyQ),
+    ("KeyR", KeyCode::KeyR),
+    ("KeyS", KeyCode::KeyS),
+    ("KeyT", KeyCode::KeyT),
+    ("KeyU", KeyCode::KeyU),
+    ("KeyV", KeyCode::KeyV),
+    ("KeyW", KeyCode::KeyW),
+    ("KeyX", KeyCode::KeyX),
+    ("KeyY", KeyCode::KeyY),
+    ("KeyZ", KeyCode::KeyZ),
+    ("Digit0", KeyCode::Digit0),
+    ("Digit1", KeyCode::Digit1),
+    ("Digit2", KeyCode::Digit2),
+    ("Digit3", KeyCode::Digit3),
+    ("Digit4", KeyCode::Digit4),
+    ("Digit5", KeyCode::Digit5),
+    ("Digit6", KeyCode::Digit6),
+    ("Digit7", KeyCode::Digit7),
+    ("Digit8", KeyCode::Digit8),
+    ("Digit9", KeyCode::Digit9),
+    ("Numpad0", KeyCode::Numpad0),
+    ("Numpad1", KeyCode::Numpad1),
+    ("Numpad2", KeyCode::Numpad2),
+    ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4),
+    ("Numpad5", KeyCode::Numpad5),
+    ("Numpad6", KeyCode::Numpad6),
+    ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8),
+    ("Numpad9", KeyCode::Numpad9),
+    ("ArrowUp", KeyCode::ArrowUp),
+    ("ArrowDown", KeyCode::ArrowDown),
+    ("ArrowLeft", KeyCode::ArrowLeft),
+    ("ArrowRight", KeyCode::ArrowRight),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("ShiftLeft", KeyCode::ShiftLeft),
+    ("ShiftRight", KeyCode::ShiftRight),
+    ("ControlLeft", KeyCode::ControlLeft),
+    ("ControlRight", KeyCode::ControlRight),
+    ("AltLeft", KeyCode::AltLeft),
+    ("AltRight", KeyCode::AltRight),
+    ("Comma", KeyCode::Comma),
+    ("Period", KeyCode::Period),
+    ("Slash", KeyCode::Slash),
+    ("Semicolon", KeyCode::Semicolon),
+    ("Quote", KeyCode::Quote),
+    ("BracketLeft", KeyCode::BracketLeft),
+    ("BracketRight", KeyCode::BracketRight),
+    ("Backslash", KeyCode::Backslash),
+    ("Minus", KeyCode::Minus),
+    ("Equal", KeyCode::Equal),
+];
+
+const GAMEPAD_BUTTONS: &[(&str, GamepadButton)] = &[
+    ("South", GamepadButton::South),
+    ("East", GamepadButton::East),
+    ("North", GamepadButton::North),
+    ("West", GamepadButton::West),
+    ("C", GamepadButton::C),
+    ("Z", GamepadButton::Z),
+    ("LeftTrigger", GamepadButton::LeftTrigger),
+    ("LeftTrigger2", GamepadButton::LeftTrigger2),
+    ("RightTrigger", GamepadButton::RightTrigger),
+    ("RightTrigger2", GamepadButton::RightTrigger2),
+    ("Select", GamepadButton::Select),
+    ("Start", GamepadButton::Start),
+    ("Mode", GamepadButton::Mode),
+    ("LeftThumb", GamepadButton::LeftThumb),
+    ("RightThumb", GamepadButton::RightThumb),
+    ("DPadUp", GamepadButton::DPadUp),
+    ("DPadDown", GamepadButton::DPadDown),
+    ("DPadLeft", GamepadButton::DPadLeft),
+    ("DPadRight", GamepadButton::DPadRight),
+];
+
+const GAMEPAD_AXES: &[(&str, GamepadAxis)] = &[
+    ("LeftStickX", GamepadAxis::LeftStickX),
+    ("LeftStickY", GamepadAxis::LeftStickY),
+    ("LeftZ", GamepadAxis::LeftZ),
+    ("RightStickX", GamepadAxis::RightStickX),
+    ("RightStickY", GamepadAxis::RightStickY),
+    ("RightZ", GamepadAxis::RightZ),
+];
@@ -1,6 +1,10 @@
 mod control_plugin;
+#[cfg(feature = "ui")]
+pub mod local_input;
 pub use control_plugin::ControlPlugin;
 
+use std::collections::VecDeque;
+
 #[derive(Clone, Debug)]
 pub struct ShipInput {
     pub thrust: f32,
@@ -15,3 +19,24 @@ impl Default for ShipInput {
         }
     }
 }
+
+/// An input held from `start_tick` up to, but not including, `end_tick`.
+#[derive(Clone, Debug)]
+pub struct ScheduledInput {
+    pub input: ShipInput,
+    pub start_tick: u64,
+    pub end_tick: u64,
+}
+
+/// The input a player's queued sequence gives at `tick`, dropping steps that are over.
+///
+/// Returns `None` before the sequence starts, and once it has finished.
+pub fn scheduled_input(queue: &mut VecDeque<ScheduledInput>, tick: u64) -> Option<ShipInput> {
+    while queue.front().is_some_and(|step| step.end_tick <= tick) {
+        queue.pop_front();
+    }
+    queue
+        .front()
+        .filter(|step| step.start_tick <= tick)
+        .map(|step| step.input.clone())
+}
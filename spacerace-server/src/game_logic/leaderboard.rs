@@ -141,14 +141,16 @@ fn update_leaderboard_ui_system(
         return;
     };
 
-    // Sort players by their finish time, name
+    // Sort players by their finish time, still racing before DNF, name
     let mut players = game.players.clone();
     players.sort_by(|a, b| {
+        let dnf = |id| game.did_not_finish.contains(id);
         game.finish_times
             .get(&a.id)
             .unwrap_or(&f32::MAX)
             .partial_cmp(game.finish_times.get(&b.id).unwrap_or(&f32::MAX))
             .unwrap()
+            .then_with(|| dnf(&a.id).cmp(&dnf(&b.id)))
             .then_with(|| a.name.cmp(&b.name))
     });
 
@@ -248,16 +250,23 @@ fn update_leaderboard_ui_system(
 
         // Format the rank and time
         let optional_finish_time = game.finish_times.get(&player.id);
-        let finish_time = optional_finish_time
-            .map_or(format!("{:.2}", game_time.elapsed_secs()), |&time| {
+        let finish_time = if game.did_not_finish.contains(&player.id) {
+            "DNF".to_string()
+        } else {
+            optional_finish_time.map_or(format!("{:.2}", game_time.elapsed_secs()), |&time| {
                 format!("{:.2}", time)
-            });
+            })
+        };
 
         let rank = match optional_finish_time {
             Some(_) => format!("{}.", i + 1),
             None => "-".to_string(),
         };
-        let score_text = format!("{}: {} {}", rank, player.name, finish_time);
+        let mut score_text = format!("{}: {} {}", rank, player.name, finish_time);
+        // Show how much hull is left for ships still racing
+        if let Some(ship) = game.ships.iter().find(|ship| ship.id == player.id) {
+            score_text.push_str(&format!(" ({:.0}hp)", ship.health));
+        }
 
         // Update the text in place by inserting a new Text component
         // or by using an entity command with `.insert()`.
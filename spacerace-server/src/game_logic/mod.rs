@@ -1,19 +1,25 @@
 mod leaderboard;
+mod moving_obstacles;
 mod server_state;
+pub(crate) mod spawn;
 
 use crate::app_state::AppState;
 use crate::components::ship::ControllableShip;
 use crate::components::ship::Ship;
 use crate::game_logic::leaderboard::LeaderBoardPlugin;
+use crate::game_logic::moving_obstacles::MovingObstaclesPlugin;
 use crate::game_state::{GameState, GameStatus};
-use crate::map::Map;
+use crate::map::generator::MapGenerator;
+use crate::map::{Map, MapBounds, VectorObject};
+use crate::physics::drift_physics_plugin::apply_bounds_system;
+use crate::ship_class::{ShipClass, ShipCollider};
 use crate::{components, game_state};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::prelude::{IndexedRandom, SliceRandom};
 use rand::Rng;
 pub use server_state::ServerState;
 use std::time::Duration;
+use uuid::Uuid;
 
 pub struct GameLogicPlugin;
 
@@ -24,6 +30,16 @@ impl Plugin for GameLogicPlugin {
                 Update,
                 check_all_players_finished_system.run_if(in_state(ServerState::Active)),
             )
+            .add_systems(
+                Update,
+                (
+                    // After ships leaving the map are eliminated, so a ship isn't despawned
+                    // twice and one that respawns is back the same frame
+                    respawn_ships_system.after(apply_bounds_system),
+                    update_ship_contact_system,
+                )
+                    .run_if(in_state(ServerState::Active)),
+            )
             .add_systems(
                 OnEnter(ServerState::Active),
                 (setup_scene, spawn_ships, start_game)
@@ -43,14 +59,15 @@ impl Plugin for GameLogicPlugin {
                 OnExit(ServerState::Active),
                 (cleanup_finished_game, cleanup_transition_timer),
             )
-            .add_plugins(LeaderBoardPlugin);
+            .add_plugins((LeaderBoardPlugin, MovingObstaclesPlugin));
     }
 }
 
-pub fn start_game(mut commands: Commands, app_state: Res<AppState>) {
+pub fn start_game(mut commands: Commands, app_state: Res<AppState>, time: Res<Time>) {
     let mut active_game_guard = app_state.active_game.lock().unwrap();
     if let Some(active_game) = active_game_guard.as_mut() {
         active_game.state = game_state::GameStatus::Running;
+        active_game.started_at = time.elapsed_secs();
     } else {
         info!("No active game to start");
     }
@@ -72,64 +89,270 @@ pub fn spawn_ships(
     app_state: Res<AppState>,
 ) {
     // Spawn a Ship for each player in the active GameState
-    let sprite_size = 25.0;
     let mut rng = rand::thread_rng();
 
     let active_game_guard = app_state.active_game.lock().unwrap();
     if let Some(active_game) = active_game_guard.as_ref() {
-        let sprite_image = asset_server.load(
-            active_game
-                .map
-                .ship_path
-                .clone()
-                .unwrap_or("ferris.png".to_string())
-                .clone(),
+        let sprite_image = ship_sprite(&asset_server, &active_game.map);
+
+        let ship_classes = app_state.ship_classes.lock().unwrap();
+
+        // The race is only starting, so no time has passed for the contact mode
+        let ships_collide = active_game.map.ship_contact.ships_collide(0.0);
+
+        // Give each ship its own spot in the start regions
+        let positions = spawn::spawn_positions(
+            &active_game.map,
+            active_game.players.len(),
+            spawn::spawn_seed(active_game.game_id),
         );
 
-        for player in &active_game.players {
+        for (player, position) in active_game.players.iter().zip(positions) {
             tracing::info!("Adding ship for player {:?}", player.id);
             // Generate a random hue for this player's ship
             let hue = rng.random_range(0.0..360.0);
             let color = Color::hsl(hue, 0.8, 0.5);
 
-            // Pick a random position for the ship from the map's start zones
-            let start_region = active_game.map.start_regions.choose(&mut rng).unwrap();
-
-            commands.spawn((
-                components::ActiveGameEntity,
-                components::ship::ControllableShip {
-                    id: player.id,
-                    impulse: 8_000.0,
-                    torque_impulse: 8_000.0,
-                },
-                Sprite {
-                    //color,
-                    image: sprite_image.clone(),
-                    custom_size: Some(Vec2::new(sprite_size, sprite_size)),
-                    ..Default::default()
-                },
-                // TODO sample position within the region's polygon
-                Transform::from_xyz(start_region.position.x, start_region.position.y, 0.0),
-                RigidBody::Dynamic,
-                Damping {
-                    linear_damping: 0.2,
-                    angular_damping: 0.5,
-                },
-                ExternalImpulse::default(),
-                AdditionalMassProperties::Mass(200.0),
-                Restitution::coefficient(0.9),
-                Friction::coefficient(0.5),
-                Collider::ball(sprite_size / 2.0),
-                Velocity::default(),
-                ActiveEvents::COLLISION_EVENTS,
-                ContactForceEventThreshold(10.0),
-            ));
+            spawn_ship(
+                &mut commands,
+                sprite_image.clone(),
+                &active_game.map,
+                player.id,
+                ship_classes.for_player(player.ship_class.as_deref()),
+                position,
+                ships_collide,
+            );
         }
     } else {
         info!("No active game to spawn ships for");
     }
 }
 
+pub(crate) fn ship_sprite(asset_server: &AssetServer, map: &Map) -> Handle<Image> {
+    asset_server.load(map.ship_path.clone().unwrap_or("ferris.png".to_string()))
+}
+
+/// Spawns a player's ship at rest at `position`, with a full hull and fuel tank.
+///
+/// `ships_collide` is whether the game's contact mode currently has ships bump into each
+/// other, so a ship spawned mid-race doesn't collide until the contact system catches up.
+pub(crate) fn spawn_ship(
+    commands: &mut Commands,
+    sprite_image: Handle<Image>,
+    map: &Map,
+    player_id: Uuid,
+    class: &ShipClass,
+    position: Vec2,
+    ships_collide: bool,
+) {
+    let mut ship = commands.spawn((
+        components::ActiveGameEntity,
+        components::ship::ControllableShip {
+            id: player_id,
+            class: class.name.clone(),
+            impulse: class.impulse,
+            torque_impulse: class.torque_impulse,
+        },
+        Sprite {
+            //color,
+            image: sprite_image,
+            // Ship images are square, so fit one around the collider rather than stretch it
+            custom_size: Some(Vec2::splat(class.collider.size().max_element())),
+            ..Default::default()
+        },
+        Transform::from_xyz(position.x, position.y, 0.0),
+        ship_physics(class, ships_collide),
+        components::ship::Health(map.hull.health),
+    ));
+    if let Some(budget) = &map.fuel {
+        ship.insert(components::ship::Fuel {
+            remaining: budget.capacity,
+            budget: budget.clone(),
+        });
+    }
+}
+
+/// Puts ships back in the race at their last checkpoint, or a start region if they haven't
+/// reached one, adding the map's time penalty.
+///
+/// Respawns are requested through `/respawn` and automatically when a ship is eliminated
+/// on maps that allow it.
+pub fn respawn_ships_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    app_state: Res<AppState>,
+    time: Res<Time>,
+    ship_query: Query<(Entity, &ControllableShip, &Transform)>,
+) {
+    let mut active_game_lock = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_lock.as_mut() else {
+        return;
+    };
+    if active_game.respawn_requests.is_empty() {
+        return;
+    }
+
+    let sprite_image = ship_sprite(&asset_server, &active_game.map);
+    let ship_classes = app_state.ship_classes.lock().unwrap();
+    let ships_collide = active_game.ships_collide(time.elapsed_secs());
+
+    // Where ships are racing, so respawned ones aren't put on top of them
+    let respawning = &active_game.respawn_requests;
+    let mut occupied: Vec<Vec2> = ship_query
+        .iter()
+        .filter(|(_, ship, _)| !respawning.contains(&ship.id))
+        .map(|(_, _, transform)| transform.translation.truncate())
+        .collect();
+
+    for player_id in std::mem::take(&mut active_game.respawn_requests) {
+        if active_game.finish_times.contains_key(&player_id)
+            || active_game.did_not_finish.contains(&player_id)
+        {
+            continue;
+        }
+
+        // Ships asking to respawn are still in the race, take them out first
+        for (entity, ship, _) in ship_query.iter() {
+            if ship.id == player_id {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let Some(player_index) = active_game
+            .players
+            .iter()
+            .position(|player| player.id == player_id)
+        else {
+            continue;
+        };
+        let ship_class = active_game.players[player_index].ship_class.clone();
+
+        let position = match active_game.checkpoints_reached.get(&player_id) {
+            Some(&index) => spawn::respawn_position(
+                std::slice::from_ref(&active_game.map.checkpoints[index]),
+                None,
+                &occupied,
+            ),
+            None => {
+                // Back to the ship's original start position, if no one is sitting there
+                let positions = spawn::spawn_positions(
+                    &active_game.map,
+                    active_game.players.len(),
+                    spawn::spawn_seed(active_game.game_id),
+                );
+                spawn::respawn_position(
+                    &active_game.map.start_regions,
+                    Some(positions[player_index]),
+                    &occupied,
+                )
+            }
+        };
+        occupied.push(position);
+
+        *active_game.penalties.entry(player_id).or_default() += active_game.map.respawn.penalty;
+        info!(player.id=?player_id, ?position, "Respawning ship");
+        spawn_ship(
+            &mut commands,
+            sprite_image.clone(),
+            &active_game.map,
+            player_id,
+            ship_classes.for_player(ship_class.as_deref()),
+            position,
+            ships_collide,
+        );
+    }
+}
+
+/// The rigid body and collider each ship of a class is simulated with, in the collision
+/// groups for whether ships currently collide.
+pub(crate) fn ship_physics(class: &ShipClass, ships_collide: bool) -> impl Bundle {
+    let collider = match class.collider {
+        ShipCollider::Ball { radius } => Collider::ball(radius),
+        ShipCollider::Capsule {
+            half_length,
+            radius,
+        } => Collider::capsule_y(half_length, radius),
+        ShipCollider::Cuboid {
+            half_width,
+            half_height,
+        } => Collider::cuboid(half_width, half_height),
+    };
+
+    (
+        RigidBody::Dynamic,
+        Damping {
+            linear_damping: class.linear_damping,
+            angular_damping: class.angular_damping,
+        },
+        ExternalImpulse::default(),
+        ExternalForce::default(),
+        AdditionalMassProperties::Mass(class.mass),
+        ReadMassProperties::default(),
+        Restitution::coefficient(0.9),
+        Friction::coefficient(0.5),
+        collider,
+        Velocity::default(),
+        // Continuous collision detection stops fast ships tunnelling through thin obstacles
+        Ccd::enabled(),
+        ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS,
+        ContactForceEventThreshold(10.0),
+        ship_collision_groups(ships_collide),
+    )
+}
+
+/// Collision group all ships are in, so they can be told to ignore each other.
+pub(crate) const SHIP_GROUP: Group = Group::GROUP_1;
+
+/// Collision groups for a ship, which flies through other ships when `ships_collide` is off.
+pub(crate) fn ship_collision_groups(ships_collide: bool) -> CollisionGroups {
+    let filter = if ships_collide {
+        Group::ALL
+    } else {
+        Group::ALL - SHIP_GROUP
+    };
+    CollisionGroups::new(SHIP_GROUP, filter)
+}
+
+/// Switches ship-to-ship collisions on or off as the game's contact mode requires.
+pub fn update_ship_contact_system(
+    app_state: Res<AppState>,
+    time: Res<Time>,
+    mut query: Query<&mut CollisionGroups, With<ControllableShip>>,
+) {
+    let active_game_lock = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_lock.as_ref() else {
+        return;
+    };
+    let groups = ship_collision_groups(active_game.ships_collide(time.elapsed_secs()));
+
+    for mut ship_groups in query.iter_mut() {
+        if *ship_groups != groups {
+            *ship_groups = groups;
+        }
+    }
+}
+
+/// Builds a solid collider for a closed map polygon.
+///
+/// The polygon is decomposed into convex parts, so unlike a polyline it has an interior
+/// that ships can't end up trapped inside.
+pub(crate) fn polygon_collider(object: &VectorObject) -> Collider {
+    // Tiled polygons repeat the first point at the end to close the shape
+    let mut vertices = object.polygon.clone();
+    if vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+
+    if vertices.len() < 3 {
+        warn!("Degenerate polygon with {} vertices", vertices.len());
+        return Collider::polyline(object.polygon.clone(), None);
+    }
+
+    let count = vertices.len() as u32;
+    let indices: Vec<[u32; 2]> = (0..count).map(|i| [i, (i + 1) % count]).collect();
+    Collider::convex_decomposition(&vertices, &indices)
+}
+
 pub fn setup_scene(
     mut commands: Commands,
     mut rapier_config: Query<&mut RapierConfiguration>,
@@ -145,12 +368,38 @@ pub fn setup_scene(
         rapier_config.single_mut().gravity = Vec2::Y * map.gravity;
 
         // Obstacles
-        for obstacle in &map.obstacles {
-            commands.spawn((
+        for (index, obstacle) in map.obstacles.iter().enumerate() {
+            let mut entity = commands.spawn((
                 components::ActiveGameEntity,
                 Transform::from_xyz(obstacle.position.x, obstacle.position.y, 0.0),
-                Collider::polyline(obstacle.polygon.clone(), None),
+                polygon_collider(obstacle),
             ));
+            insert_physical_properties(&mut entity, obstacle);
+
+            if let Some(motion) = &obstacle.motion {
+                // Kinematic bodies push ships out of the way instead of being pushed
+                entity.insert((
+                    RigidBody::KinematicPositionBased,
+                    components::obstacle::MovingObstacle {
+                        index,
+                        motion: motion.clone(),
+                        origin: obstacle.position,
+                        centre: obstacle.centre(),
+                        elapsed: 0.0,
+                    },
+                ));
+            }
+        }
+
+        // Map edges
+        if map.bounds == MapBounds::Walls {
+            for (position, half_extents) in boundary_walls(map.size) {
+                commands.spawn((
+                    components::ActiveGameEntity,
+                    Transform::from_xyz(position.x, position.y, 0.0),
+                    Collider::cuboid(half_extents.x, half_extents.y),
+                ));
+            }
         }
 
         // Finish zone colliders
@@ -165,12 +414,48 @@ pub fn setup_scene(
                 // },
                 Transform::from_xyz(finish.position.x, finish.position.y, 0.0),
                 // Note we only handle Tiled polygons, ideally handle rectangle objects etc
-                Collider::polyline(finish.polygon.clone(), None),
+                polygon_collider(finish),
                 Sensor,
                 crate::components::FinishRegion,
             ));
         }
 
+        // Checkpoints
+        for (index, checkpoint) in map.checkpoints.iter().enumerate() {
+            commands.spawn((
+                components::ActiveGameEntity,
+                Transform::from_xyz(checkpoint.position.x, checkpoint.position.y, 0.0),
+                polygon_collider(checkpoint),
+                Sensor,
+                components::CheckpointRegion { index },
+            ));
+        }
+
+        // Boost pads and slow zones
+        for pad in &map.boost_pads {
+            commands.spawn((
+                components::ActiveGameEntity,
+                Transform::from_xyz(pad.zone.position.x, pad.zone.position.y, 0.0),
+                polygon_collider(&pad.zone),
+                Sensor,
+                components::BoostRegion {
+                    strength: pad.strength,
+                    direction: pad.direction,
+                },
+            ));
+        }
+        for zone in &map.slow_zones {
+            commands.spawn((
+                components::ActiveGameEntity,
+                Transform::from_xyz(zone.zone.position.x, zone.zone.position.y, 0.0),
+                polygon_collider(&zone.zone),
+                Sensor,
+                components::SlowRegion {
+                    damping: zone.damping,
+                },
+            ));
+        }
+
         // Skin
         if let Some(skin_path) = &map.skin_path {
             info!("Spawning background skin from: {}", skin_path);
@@ -196,12 +481,64 @@ pub fn setup_scene(
     }
 }
 
+/// Positions and half extents of four walls enclosing a map of the given size.
+pub(crate) fn boundary_walls(map_size: Vec2) -> [(Vec2, Vec2); 4] {
+    // Thick enough that a ship can't be pushed through
+    const THICKNESS: f32 = 100.0;
+    let half_size = map_size / 2.0;
+    let offset = half_size + THICKNESS / 2.0;
+
+    let vertical = Vec2::new(THICKNESS / 2.0, half_size.y + THICKNESS);
+    let horizontal = Vec2::new(half_size.x + THICKNESS, THICKNESS / 2.0);
+    [
+        (Vec2::new(-offset.x, 0.0), vertical),
+        (Vec2::new(offset.x, 0.0), vertical),
+        (Vec2::new(0.0, -offset.y), horizontal),
+        (Vec2::new(0.0, offset.y), horizontal),
+    ]
+}
+
+/// Removes a wrecked or lost ship. It respawns if the map allows, otherwise its player
+/// did not finish.
+pub(crate) fn eliminate_ship(
+    commands: &mut Commands,
+    entity: Entity,
+    ship: &ControllableShip,
+    game: &mut GameState,
+) {
+    if game.map.respawn.enabled {
+        info!(player.id=?ship.id, "Ship wrecked, respawning");
+        game.respawn_requests.push(ship.id);
+    } else {
+        info!(player.id=?ship.id, "Ship eliminated");
+        game.did_not_finish.insert(ship.id);
+    }
+    commands.entity(entity).despawn_recursive();
+}
+
+/// Applies the optional physical properties set on a map object to its collider.
+fn insert_physical_properties(entity: &mut EntityCommands, object: &VectorObject) {
+    if let Some(restitution) = object.restitution {
+        entity.insert(Restitution::coefficient(restitution));
+    }
+    if let Some(friction) = object.friction {
+        entity.insert(Friction::coefficient(friction));
+    }
+    if let Some(damage) = object.damage {
+        entity.insert(components::Damage(damage));
+    }
+    if object.sensor {
+        entity.insert(Sensor);
+    }
+}
+
 // System to check if all players finished the race
 pub fn check_all_players_finished_system(app_state: Res<AppState>, mut commands: Commands) {
     let mut active_game_lock = app_state.active_game.lock().unwrap();
     if let Some(active_game) = active_game_lock.as_mut() {
         if active_game.state == GameStatus::Running
-            && active_game.players.len() == active_game.finish_times.len()
+            && active_game.players.len()
+                == active_game.finish_times.len() + active_game.did_not_finish.len()
         {
             info!("All players have finished the race! Transitioning game state to Finished.");
             active_game.state = GameStatus::Finished;
@@ -249,15 +586,21 @@ pub fn game_scheduler_system(
                 // Remove the pending game from the lobby
                 let pending_game = lobby.remove(index);
 
-                let map = maps.get(pending_game.map_id.1).unwrap();
+                // Generated maps aren't loaded as assets, build them from their name instead
+                let mut map = match maps.get(pending_game.map_id.1) {
+                    Some(map) => map.clone(),
+                    None => MapGenerator::from_name(&pending_game.map_id.0)
+                        .expect("Pending game refers to an unknown map")
+                        .generate(),
+                };
+                if let Some(ship_contact) = pending_game.ship_contact {
+                    map.ship_contact = ship_contact;
+                }
 
                 // Create a new GameState from the pending game
-                let game_state = GameState::new(
-                    pending_game.game_id,
-                    pending_game.players.clone(),
-                    map.clone(),
-                )
-                .expect("Failed to create GameState from PendingGame");
+                let game_state =
+                    GameState::new(pending_game.game_id, pending_game.players.clone(), map)
+                        .expect("Failed to create GameState from PendingGame");
 
                 tracing::info!(game.id=?game_state.game_id, state=?game_state, "Starting game");
 
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::app_state::AppState;
+use crate::components::obstacle::{MovingObstacle, Obstacle};
+use crate::game_logic::server_state::ServerState;
+
+/// Animates obstacles that have a `Motion` and publishes where they are.
+pub struct MovingObstaclesPlugin;
+
+impl Plugin for MovingObstaclesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            move_obstacles_system.run_if(in_state(ServerState::Active)),
+        )
+        .add_systems(
+            PostUpdate,
+            update_obstacle_state_system.run_if(in_state(ServerState::Active)),
+        );
+    }
+}
+
+/// Race time obstacles move on by each frame, whatever the real frame time.
+pub const OBSTACLE_SECONDS_PER_TICK: f32 = 1.0 / 60.0;
+
+/// Moves each obstacle to its pose for the number of ticks since the race started.
+///
+/// The pose only depends on the tick count, not the frame rate, so every race on the same
+/// map plays out the same way and bots can predict where an obstacle will be.
+fn move_obstacles_system(mut query: Query<(&mut MovingObstacle, &mut Transform)>) {
+    for (mut obstacle, mut transform) in query.iter_mut() {
+        obstacle.elapsed += OBSTACLE_SECONDS_PER_TICK;
+
+        let rotation = Quat::from_rotation_z(obstacle.motion.rotation_at(obstacle.elapsed));
+        // Spin about the centre of the polygon rather than its first point
+        let centre = obstacle.centre.extend(0.0);
+        let position = obstacle.origin + obstacle.motion.offset_at(obstacle.elapsed);
+
+        transform.translation = position.extend(0.0) + centre - rotation * centre;
+        transform.rotation = rotation;
+    }
+}
+
+fn update_obstacle_state_system(
+    app_state: Res<AppState>,
+    query: Query<(&MovingObstacle, &Transform)>,
+) {
+    let mut active_game_lock = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_lock.as_mut() else {
+        return;
+    };
+
+    let mut obstacles: Vec<Obstacle> = query
+        .iter()
+        .map(|(obstacle, transform)| Obstacle {
+            index: obstacle.index,
+            position: (transform.translation.x, transform.translation.y),
+            rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+        })
+        .collect();
+    obstacles.sort_by_key(|obstacle| obstacle.index);
+    active_game.obstacles = obstacles;
+}
@@ -0,0 +1,150 @@
+use bevy::prelude::Vec2;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use uuid::Uuid;
+
+use crate::map::{Map, VectorObject};
+
+/// Minimum distance between ship start positions, comfortably more than a ship's width.
+pub const SHIP_SPACING: f32 = 40.0;
+
+/// The seed a game's start positions are shuffled with.
+pub fn spawn_seed(game_id: Uuid) -> u64 {
+    let (high, low) = game_id.as_u64_pair();
+    high ^ low
+}
+
+/// Picks `count` start positions at least `SHIP_SPACING` apart.
+///
+/// The map's `spawn` points are used first, then points on a grid inside the start regions.
+/// Which ship gets which position is shuffled by `seed`, so the same seed always gives the
+/// same start, while no player is always stuck at the back.
+pub fn spawn_positions(map: &Map, count: usize, seed: u64) -> Vec<Vec2> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut positions: Vec<Vec2> = vec![];
+    for &point in &map.spawn_points {
+        if is_clear(&positions, point) {
+            positions.push(point);
+        }
+    }
+
+    if positions.len() < count {
+        let mut grid_points = vec![];
+        for region in &map.start_regions {
+            for point in grid_points_inside(region) {
+                if is_clear(&positions, point) && is_clear(&grid_points, point) {
+                    grid_points.push(point);
+                }
+            }
+        }
+        grid_points.shuffle(&mut rng);
+        grid_points.truncate(count - positions.len());
+        positions.extend(grid_points);
+    }
+
+    // Start regions too small for everyone, line the rest up beside the first region
+    let anchor = map
+        .start_regions
+        .first()
+        .map_or(Vec2::ZERO, |region| region.position);
+    let mut overflow = 1..;
+    while positions.len() < count {
+        let point = anchor + Vec2::X * SHIP_SPACING * overflow.next().unwrap() as f32;
+        if is_clear(&positions, point) {
+            tracing::warn!(
+                ?point,
+                "Not enough room in the start regions, spawning outside"
+            );
+            positions.push(point);
+        }
+    }
+
+    positions.truncate(count);
+    positions.shuffle(&mut rng);
+    positions
+}
+
+/// Picks a spot for a ship respawning in `regions`, at least `SHIP_SPACING` from the
+/// `occupied` positions of the ships already racing.
+///
+/// `preferred` is tried first, then points inside the regions nearest their middles. When
+/// there's no room left, the ship is lined up along the first region from its middle.
+pub fn respawn_position(
+    regions: &[VectorObject],
+    preferred: Option<Vec2>,
+    occupied: &[Vec2],
+) -> Vec2 {
+    let mut candidates: Vec<Vec2> = preferred.into_iter().collect();
+    for region in regions {
+        let centre = region.position + region.centre();
+        let mut points = grid_points_inside(region);
+        points.sort_by(|a, b| a.distance(centre).total_cmp(&b.distance(centre)));
+        candidates.extend(points);
+    }
+    if let Some(point) = candidates
+        .into_iter()
+        .find(|&point| is_clear(occupied, point))
+    {
+        return point;
+    }
+
+    // Along the longer side, so ships respawning at a gate line up across it
+    let (centre, axis) = regions.first().map_or((Vec2::ZERO, Vec2::X), |region| {
+        let (min, max) = polygon_bounds(region);
+        let size = max - min;
+        let axis = if size.x >= size.y { Vec2::X } else { Vec2::Y };
+        (region.position + region.centre(), axis)
+    });
+    let mut step = 1..;
+    loop {
+        let offset = axis * SHIP_SPACING * step.next().unwrap() as f32;
+        for point in [centre + offset, centre - offset] {
+            if is_clear(occupied, point) {
+                return point;
+            }
+        }
+    }
+}
+
+fn is_clear(taken: &[Vec2], point: Vec2) -> bool {
+    taken
+        .iter()
+        .all(|other| other.distance(point) >= SHIP_SPACING)
+}
+
+/// Points `SHIP_SPACING` apart covering the polygon, far enough from its edges for a ship.
+fn grid_points_inside(region: &VectorObject) -> Vec<Vec2> {
+    let (min, max) = polygon_bounds(region);
+    let min = region.position + min + Vec2::splat(SHIP_SPACING / 2.0);
+    let max = region.position + max - Vec2::splat(SHIP_SPACING / 2.0);
+
+    let mut points = vec![];
+    let mut y = min.y;
+    while y <= max.y {
+        let mut x = min.x;
+        while x <= max.x {
+            let point = Vec2::new(x, y);
+            if region.contains(point) {
+                points.push(point);
+            }
+            x += SHIP_SPACING;
+        }
+        y += SHIP_SPACING;
+    }
+
+    // Too narrow for the grid, the middle will have to do
+    if points.is_empty() {
+        points.push(region.position + region.centre());
+    }
+    points
+}
+
+/// Corners of the box around the polygon, relative to its position.
+fn polygon_bounds(region: &VectorObject) -> (Vec2, Vec2) {
+    region.polygon.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), point| (min.min(*point), max.max(*point)),
+    )
+}
@@ -1,28 +1,37 @@
+use crate::components::obstacle::Obstacle;
 use crate::components::ship::Ship;
 use crate::components::Player;
-use crate::map::{Map, NamedMapId};
+use crate::map::{Map, NamedMapId, ShipContact};
 
 use bevy::asset::AssetId;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-pub enum GameStatus {
-    Queued,
-    Running,
-    Finished,
-}
+pub use spacerace_protocol::GameStatus;
 
 #[derive(Debug, Clone)]
 pub struct GameState {
     pub game_id: Uuid,
     pub players: Vec<Player>,
     pub finish_times: HashMap<Uuid, f32>,
+    /// Players knocked out of the race before reaching the finish
+    pub did_not_finish: HashSet<Uuid>,
+    /// Index of the checkpoint each player passed through most recently
+    pub checkpoints_reached: HashMap<Uuid, usize>,
+    /// Seconds added to each player's finish time, from respawning
+    pub penalties: HashMap<Uuid, f32>,
+    /// Players whose ships should be put back at their last checkpoint
+    pub respawn_requests: Vec<Uuid>,
 
     pub ships: Vec<Ship>,
+    /// Current poses of the map's moving obstacles
+    pub obstacles: Vec<Obstacle>,
     pub map: Map,
     pub state: GameStatus,
+    /// App time in seconds when the race started
+    pub started_at: f32,
+    /// Frames simulated since the race started, for scheduling control inputs
+    pub tick: u64,
 }
 
 impl GameState {
@@ -35,11 +44,23 @@ impl GameState {
             game_id: game_id,
             players: players.clone(),
             ships: vec![],
+            obstacles: vec![],
             map,
             state: GameStatus::Queued,
+            started_at: 0.0,
+            tick: 0,
             finish_times: HashMap::new(),
+            did_not_finish: HashSet::new(),
+            checkpoints_reached: HashMap::new(),
+            penalties: HashMap::new(),
+            respawn_requests: vec![],
         })
     }
+
+    /// Whether ships bump into each other at app time `now`, going by the contact mode.
+    pub fn ships_collide(&self, now: f32) -> bool {
+        self.map.ship_contact.ships_collide(now - self.started_at)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +68,8 @@ pub struct PendingGame {
     pub game_id: Uuid,
     pub players: Vec<Player>,
     pub map_id: NamedMapId,
+    /// Overrides the map's ship contact mode when set
+    pub ship_contact: Option<ShipContact>,
 }
 
 impl PendingGame {
@@ -55,6 +78,7 @@ impl PendingGame {
             game_id: Uuid::new_v4(),
             players: vec![],
             map_id,
+            ship_contact: None,
         }
     }
 }
@@ -25,6 +25,30 @@ fn setup_graphics(mut commands: Commands) {
     ));
 }
 
+/// Maps without a skin (e.g. generated tracks) have no artwork showing where the
+/// obstacles are, so outline the map geometry instead.
+#[cfg(feature = "ui")]
+fn draw_unskinned_map_system(app_state: Res<crate::app_state::AppState>, mut gizmos: Gizmos) {
+    let active_game = app_state.active_game.lock().unwrap();
+    let Some(game) = active_game.as_ref() else {
+        return;
+    };
+    if game.map.skin_path.is_some() {
+        return;
+    }
+
+    let outlines = [
+        (&game.map.obstacles, Color::srgb(0.2, 0.2, 0.3)),
+        (&game.map.start_regions, Color::srgb(0.2, 0.7, 0.2)),
+        (&game.map.finish_regions, Color::srgb(0.8, 0.2, 0.2)),
+    ];
+    for (objects, color) in outlines {
+        for object in objects {
+            gizmos.linestrip_2d(object.polygon.iter().map(|p| object.position + *p), color);
+        }
+    }
+}
+
 impl Plugin for GraphicsPlugin {
     fn build(&self, app: &mut App) {
         // Background
@@ -36,6 +60,12 @@ impl Plugin for GraphicsPlugin {
 
         app.add_systems(Startup, setup_graphics);
 
+        #[cfg(feature = "ui")]
+        app.add_systems(
+            Update,
+            draw_unskinned_map_system.run_if(in_state(crate::game_logic::ServerState::Active)),
+        );
+
         // TODO make a feature flag
         //app.add_plugins(RapierDebugRenderPlugin::default());
 
@@ -113,6 +113,15 @@ pub fn setup_lobby_ui(mut commands: Commands) {
                             ..default()
                         },
                     ));
+                    #[cfg(feature = "ui")]
+                    text_node.spawn((
+                        Text::new("Local players: press thrust or a gamepad's A button to join"),
+                        TextColor(Color::WHITE),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                    ));
                 });
 
             parent.spawn((
@@ -14,9 +14,10 @@ mod control;
 mod graphics_plugin;
 mod lobby_graphics_plugin;
 mod particle_effects;
+mod ship_class;
 
 use app_state::AppState;
-use map::{check_maps_loaded, load_maps, Map, MapAssetLoader};
+use map::{check_maps_loaded, load_maps, register_generated_maps, Map, MapAssetLoader};
 
 use bevy::prelude::*;
 
@@ -54,6 +55,7 @@ fn main() {
             }),
             ..default()
         }));
+        app.add_plugins(control::local_input::LocalInputPlugin);
     }
 
     #[cfg(not(feature = "ui"))]
@@ -66,7 +68,14 @@ fn main() {
         .add_plugins(GraphicsPlugin)
         .init_asset::<Map>()
         .init_asset_loader::<MapAssetLoader>()
-        .add_systems(Startup, load_maps)
+        .add_systems(
+            Startup,
+            (
+                load_maps,
+                register_generated_maps,
+                ship_class::load_ship_classes,
+            ),
+        )
         .add_systems(
             Update,
             check_maps_loaded.run_if(in_state(ServerState::Loading)),
@@ -0,0 +1,251 @@
+use bevy::prelude::Vec2;
+use std::fmt::Write;
+
+use super::{Map, MapBounds, ShipContact, VectorObject};
+
+/// A Tiled custom property as `(name, type, value)`.
+type Property = (&'static str, &'static str, String);
+
+impl Map {
+    /// Serializes the map as a Tiled `.tmx` document that `Map::from_tmx` loads back.
+    ///
+    /// Useful for opening a generated track in Tiled to tweak it by hand.
+    pub fn to_tmx(&self) -> String {
+        let width = self.size.x.round() as u32;
+        let height = self.size.y.round() as u32;
+
+        let mut tmx = String::new();
+        tmx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            tmx,
+            "<map version=\"1.8\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+             width=\"{width}\" height=\"{height}\" tilewidth=\"1\" tileheight=\"1\" infinite=\"0\">"
+        );
+
+        let mut properties = vec![("name", "string", self.name.clone())];
+        if let Some(skin_path) = &self.skin_path {
+            properties.push(("skin", "string", skin_path.clone()));
+        }
+        if let Some(ship_path) = &self.ship_path {
+            properties.push(("ship", "string", ship_path.clone()));
+        }
+        properties.push(("gravity", "float", self.gravity.to_string()));
+        let bounds = match self.bounds {
+            MapBounds::Walls => "walls",
+            MapBounds::Wrap => "wrap",
+            MapBounds::Eliminate => "eliminate",
+        };
+        properties.push(("bounds", "string", bounds.to_string()));
+        properties.push(("hull", "float", self.hull.health.to_string()));
+        let damage_scale = self.hull.damage_scale.to_string();
+        properties.push(("damage_scale", "float", damage_scale));
+        properties.push(("respawn", "bool", self.respawn.enabled.to_string()));
+        let penalty = self.respawn.penalty.to_string();
+        properties.push(("respawn_penalty", "float", penalty));
+        match self.ship_contact {
+            ShipContact::Contact => {}
+            ShipContact::Ghost => properties.push(("ship_contact", "string", "ghost".to_string())),
+            ShipContact::ContactAfter { seconds } => {
+                properties.push(("contact_after", "float", seconds.to_string()))
+            }
+        }
+        if let Some(fuel) = &self.fuel {
+            properties.push(("fuel", "float", fuel.capacity.to_string()));
+            properties.push(("fuel_burn", "float", fuel.thrust_burn.to_string()));
+            let rotation_burn = fuel.rotation_burn.to_string();
+            properties.push(("rotation_fuel_burn", "float", rotation_burn));
+        }
+        write_properties(&mut tmx, " ", properties);
+
+        tmx.push_str(" <objectgroup id=\"1\" name=\"Objects\">\n");
+        let mut next_id = 1..;
+        for (user_type, objects) in [
+            ("start", &self.start_regions),
+            ("finish", &self.finish_regions),
+            ("checkpoint", &self.checkpoints),
+        ] {
+            for object in objects {
+                let id = next_id.next().unwrap();
+                self.write_polygon(&mut tmx, id, user_type, object, vec![]);
+            }
+        }
+        for obstacle in &self.obstacles {
+            let mut properties = vec![];
+            if let Some(motion) = &obstacle.motion {
+                if !motion.path.is_empty() {
+                    let path_id = next_id.next().unwrap();
+                    self.write_path(&mut tmx, path_id, obstacle.position, &motion.path);
+                    properties.push(("path", "object", path_id.to_string()));
+                    properties.push(("speed", "float", motion.speed.to_string()));
+                }
+                let degrees = motion.rotation_speed.to_degrees();
+                properties.push(("rotation_speed", "float", degrees.to_string()));
+            }
+            let id = next_id.next().unwrap();
+            self.write_polygon(&mut tmx, id, "", obstacle, properties);
+        }
+        for zone in &self.force_zones {
+            let properties = vec![
+                (
+                    "direction",
+                    "float",
+                    zone.acceleration.to_angle().to_degrees().to_string(),
+                ),
+                ("strength", "float", zone.acceleration.length().to_string()),
+            ];
+            let id = next_id.next().unwrap();
+            self.write_polygon(&mut tmx, id, "wind", &zone.zone, properties);
+        }
+        for pad in &self.boost_pads {
+            let mut properties = vec![("strength", "float", pad.strength.to_string())];
+            if let Some(direction) = pad.direction {
+                let degrees = direction.to_angle().to_degrees();
+                properties.push(("direction", "float", degrees.to_string()));
+            }
+            let id = next_id.next().unwrap();
+            self.write_polygon(&mut tmx, id, "boost", &pad.zone, properties);
+        }
+        for zone in &self.slow_zones {
+            let properties = vec![("damping", "float", zone.damping.to_string())];
+            let id = next_id.next().unwrap();
+            self.write_polygon(&mut tmx, id, "slow", &zone.zone, properties);
+        }
+        for zone in &self.refuel_zones {
+            let properties = vec![("rate", "float", zone.rate.to_string())];
+            let id = next_id.next().unwrap();
+            self.write_polygon(&mut tmx, id, "refuel", &zone.zone, properties);
+        }
+        for well in &self.gravity_wells {
+            let position = self.to_tiled(well.position);
+            let id = next_id.next().unwrap();
+            let _ = writeln!(
+                tmx,
+                "  <object id=\"{id}\" type=\"gravity_well\" x=\"{}\" y=\"{}\">",
+                position.x, position.y
+            );
+            let properties = vec![
+                ("strength", "float", well.strength.to_string()),
+                ("falloff", "float", well.falloff.to_string()),
+            ];
+            write_properties(&mut tmx, "   ", properties);
+            tmx.push_str("   <point/>\n");
+            tmx.push_str("  </object>\n");
+        }
+        for spawn_point in &self.spawn_points {
+            let position = self.to_tiled(*spawn_point);
+            let id = next_id.next().unwrap();
+            let _ = writeln!(
+                tmx,
+                "  <object id=\"{id}\" type=\"spawn\" x=\"{}\" y=\"{}\">",
+                position.x, position.y
+            );
+            tmx.push_str("   <point/>\n");
+            tmx.push_str("  </object>\n");
+        }
+        tmx.push_str(" </objectgroup>\n");
+
+        tmx.push_str("</map>\n");
+        tmx
+    }
+
+    /// Undoes the re-centering and Y inversion applied when loading from Tiled.
+    fn to_tiled(&self, position: Vec2) -> Vec2 {
+        Vec2::new(
+            position.x + self.size.x / 2.0,
+            self.size.y / 2.0 - position.y,
+        )
+    }
+
+    fn write_polygon(
+        &self,
+        tmx: &mut String,
+        id: usize,
+        user_type: &str,
+        object: &VectorObject,
+        mut properties: Vec<Property>,
+    ) {
+        let position = self.to_tiled(object.position);
+
+        // The closing point is implied in Tiled polygons
+        let mut polygon = object.polygon.as_slice();
+        if polygon.len() > 1 && polygon.first() == polygon.last() {
+            polygon = &polygon[..polygon.len() - 1];
+        }
+        let points = polygon
+            .iter()
+            // Subtracting from zero avoids writing out `-0`
+            .map(|point| format!("{},{}", point.x, 0.0 - point.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let type_attribute = if user_type.is_empty() {
+            String::new()
+        } else {
+            format!(" type=\"{user_type}\"")
+        };
+        let _ = writeln!(
+            tmx,
+            "  <object id=\"{id}\"{type_attribute} x=\"{}\" y=\"{}\">",
+            position.x, position.y
+        );
+
+        if let Some(restitution) = object.restitution {
+            properties.push(("restitution", "float", restitution.to_string()));
+        }
+        if let Some(friction) = object.friction {
+            properties.push(("friction", "float", friction.to_string()));
+        }
+        if let Some(damage) = object.damage {
+            properties.push(("damage", "float", damage.to_string()));
+        }
+        if object.sensor {
+            properties.push(("sensor", "bool", "true".to_string()));
+        }
+        write_properties(tmx, "   ", properties);
+
+        let _ = writeln!(tmx, "   <polygon points=\"{points}\"/>");
+        tmx.push_str("  </object>\n");
+    }
+
+    /// Writes a moving obstacle's path as a polyline starting from the obstacle's position.
+    fn write_path(&self, tmx: &mut String, id: usize, start: Vec2, path: &[Vec2]) {
+        let position = self.to_tiled(start);
+        let points = path
+            .iter()
+            .map(|point| format!("{},{}", point.x, 0.0 - point.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let _ = writeln!(
+            tmx,
+            "  <object id=\"{id}\" type=\"path\" x=\"{}\" y=\"{}\">",
+            position.x, position.y
+        );
+        let _ = writeln!(tmx, "   <polyline points=\"{points}\"/>");
+        tmx.push_str("  </object>\n");
+    }
+}
+
+fn write_properties(tmx: &mut String, indent: &str, properties: Vec<Property>) {
+    if properties.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(tmx, "{indent}<properties>");
+    for (name, property_type, value) in properties {
+        let _ = writeln!(
+            tmx,
+            "{indent} <property name=\"{name}\" type=\"{property_type}\" value=\"{}\"/>",
+            escape(&value)
+        );
+    }
+    let _ = writeln!(tmx, "{indent}</properties>");
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
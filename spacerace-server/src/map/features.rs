@@ -0,0 +1,240 @@
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+use super::{Map, VectorObject};
+
+/// A point pulling ships towards it, or pushing them away with a negative strength.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GravityWell {
+    pub position: Vec2,
+    /// Acceleration in pixels/s² towards the well for a ship right on top of it
+    pub strength: f32,
+    /// Distance at which the acceleration has dropped to half strength
+    pub falloff: f32,
+}
+
+impl GravityWell {
+    pub const DEFAULT_STRENGTH: f32 = 200.0;
+    pub const DEFAULT_FALLOFF: f32 = 300.0;
+
+    pub fn acceleration_at(&self, point: Vec2) -> Vec2 {
+        let offset = self.position - point;
+        let distance = offset.length();
+        let magnitude = self.strength / (1.0 + (distance / self.falloff).powi(2));
+        offset.normalize_or_zero() * magnitude
+    }
+}
+
+/// A region pushing ships in a fixed direction, like wind or a water current.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ForceZone {
+    pub zone: VectorObject,
+    /// Acceleration in pixels/s² applied to ships inside the zone
+    pub acceleration: Vec2,
+}
+
+impl ForceZone {
+    pub const DEFAULT_STRENGTH: f32 = 100.0;
+}
+
+/// A sensor giving ships an instant kick of speed as they cross it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BoostPad {
+    pub zone: VectorObject,
+    /// Speed in pixels/s added to a ship crossing the pad
+    pub strength: f32,
+    /// Unit vector to boost ships along, or along the ship's own heading when not set
+    pub direction: Option<Vec2>,
+}
+
+impl BoostPad {
+    pub const DEFAULT_STRENGTH: f32 = 300.0;
+}
+
+/// A sensor slowing ships down while they are inside it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlowZone {
+    pub zone: VectorObject,
+    /// Linear damping added to ships inside the zone
+    pub damping: f32,
+}
+
+impl SlowZone {
+    pub const DEFAULT_DAMPING: f32 = 2.0;
+}
+
+/// Limits how long ships can fire their thrusters for. Ships out of fuel coast.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FuelBudget {
+    /// Fuel each ship starts with, and the most it can hold
+    pub capacity: f32,
+    /// Fuel used per second at full thrust
+    pub thrust_burn: f32,
+    /// Fuel used per second of full rotation
+    pub rotation_burn: f32,
+}
+
+impl FuelBudget {
+    pub const DEFAULT_THRUST_BURN: f32 = 10.0;
+    pub const DEFAULT_ROTATION_BURN: f32 = 2.0;
+
+    /// Fuel used over `delta` seconds with the given control inputs.
+    pub fn burn(&self, thrust: f32, rotation: f32, delta: f32) -> f32 {
+        (thrust.abs() * self.thrust_burn + rotation.abs() * self.rotation_burn) * delta
+    }
+}
+
+/// How much punishment ship hulls take before the ship is knocked out of the race.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Hull {
+    /// Health each ship starts with
+    pub health: f32,
+    /// Health lost per unit of contact force above `SAFE_CONTACT_FORCE`, zero for indestructible ships
+    pub damage_scale: f32,
+}
+
+impl Hull {
+    pub const DEFAULT_HEALTH: f32 = 100.0;
+    pub const DEFAULT_DAMAGE_SCALE: f32 = 0.0002;
+    /// Contact forces up to this are scrapes and gentle bumps that do no damage
+    pub const SAFE_CONTACT_FORCE: f32 = 20_000.0;
+
+    /// Health lost from a contact force, scaled by the other collider's damage multiplier.
+    pub fn damage(&self, force: f32, multiplier: f32) -> f32 {
+        (force - Self::SAFE_CONTACT_FORCE).max(0.0) * self.damage_scale * multiplier
+    }
+}
+
+impl Default for Hull {
+    fn default() -> Self {
+        Self {
+            health: Self::DEFAULT_HEALTH,
+            damage_scale: Self::DEFAULT_DAMAGE_SCALE,
+        }
+    }
+}
+
+/// Whether and how wrecked or stuck ships get back into the race.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RespawnRules {
+    /// Respawn eliminated ships automatically, instead of them not finishing
+    pub enabled: bool,
+    /// Seconds added to a player's finish time for each respawn
+    pub penalty: f32,
+}
+
+impl RespawnRules {
+    pub const DEFAULT_PENALTY: f32 = 5.0;
+}
+
+impl Default for RespawnRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            penalty: Self::DEFAULT_PENALTY,
+        }
+    }
+}
+
+/// A region topping up the fuel of ships inside it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RefuelZone {
+    pub zone: VectorObject,
+    /// Fuel added per second
+    pub rate: f32,
+}
+
+impl RefuelZone {
+    pub const DEFAULT_RATE: f32 = 20.0;
+}
+
+/// How a moving obstacle is animated during a race.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Motion {
+    /// Offsets from the obstacle's starting position to travel through. The obstacle goes
+    /// back and forth along the path, or round and round if the path ends where it started.
+    pub path: Vec<Vec2>,
+    /// Speed along the path in pixels/s
+    pub speed: f32,
+    /// Rotation speed in radians/s, anticlockwise, about the obstacle's centre
+    pub rotation_speed: f32,
+}
+
+impl Motion {
+    /// The obstacle's offset from its starting position after moving for `elapsed` seconds.
+    pub fn offset_at(&self, elapsed: f32) -> Vec2 {
+        let segments = self.path.windows(2).map(|s| (s[0], s[1]));
+        let length: f32 = segments.clone().map(|(a, b)| a.distance(b)).sum();
+        if length <= 0.0 {
+            return Vec2::ZERO;
+        }
+
+        let closed = self.path.first() == self.path.last();
+        let mut distance = (self.speed * elapsed).rem_euclid(2.0 * length);
+        if closed {
+            distance = distance.rem_euclid(length);
+        } else if distance > length {
+            // Heading back to the start
+            distance = 2.0 * length - distance;
+        }
+
+        for (a, b) in segments {
+            let segment_length = a.distance(b);
+            if distance <= segment_length {
+                return a.lerp(b, distance / segment_length);
+            }
+            distance -= segment_length;
+        }
+        *self.path.last().unwrap()
+    }
+
+    pub fn rotation_at(&self, elapsed: f32) -> f32 {
+        self.rotation_speed * elapsed
+    }
+}
+
+impl Map {
+    /// The acceleration from all gravity wells and force zones at a point.
+    pub fn force_field_at(&self, point: Vec2) -> Vec2 {
+        let wells = self
+            .gravity_wells
+            .iter()
+            .map(|well| well.acceleration_at(point));
+        let zones = self
+            .force_zones
+            .iter()
+            .filter(|zone| zone.zone.contains(point))
+            .map(|zone| zone.acceleration);
+
+        wells.chain(zones).sum()
+    }
+}
+
+impl VectorObject {
+    /// The average of the polygon's points, relative to its position.
+    pub fn centre(&self) -> Vec2 {
+        // Skip the closing point so the first point isn't counted twice
+        let points = &self.polygon[..self.polygon.len().saturating_sub(1)];
+        if points.is_empty() {
+            return Vec2::ZERO;
+        }
+        points.iter().sum::<Vec2>() / points.len() as f32
+    }
+
+    /// Whether a point in game coordinates lies inside the polygon.
+    pub fn contains(&self, point: Vec2) -> bool {
+        let point = point - self.position;
+
+        // Count the polygon edges crossed by a ray heading right from the point
+        let mut inside = false;
+        for edge in self.polygon.windows(2) {
+            let (a, b) = (edge[0], edge[1]);
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
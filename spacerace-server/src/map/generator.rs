@@ -0,0 +1,280 @@
+use bevy::prelude::Vec2;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::{Map, MapBounds, VectorObject};
+
+/// Map names starting with this prefix describe a procedurally generated track.
+pub const GENERATED_MAP_PREFIX: &str = "generated-";
+
+/// Largest width or height a generated map may have, in pixels
+pub const MAX_GENERATED_MAP_SIZE: f32 = 10_000.0;
+/// Most grid cells a generated track may be laid out on
+pub const MAX_GENERATED_MAP_CELLS: f32 = 10_000.0;
+
+/// Procedural race track generator.
+///
+/// The track is laid out on a grid of square cells, each `corridor_width` wide. A corridor
+/// is carved from a start cell on the left of the map, making `turns` changes of direction
+/// on its way right, and ending at the finish cell. Cells outside the corridor become
+/// obstacles with probability `obstacle_density`, so there is always at least one clear
+/// path from start to finish.
+///
+/// The same generator settings always produce the same map, and `name()` encodes those
+/// settings so a track can be reproduced from its map name alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapGenerator {
+    pub seed: u64,
+    pub size: Vec2,
+    pub corridor_width: f32,
+    pub turns: u32,
+    pub obstacle_density: f32,
+    pub gravity: f32,
+}
+
+impl Default for MapGenerator {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            size: Vec2::new(1920.0, 1080.0),
+            corridor_width: 120.0,
+            turns: 6,
+            obstacle_density: 0.7,
+            gravity: 0.0,
+        }
+    }
+}
+
+impl MapGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// Parses a generated map name such as `generated-42` or `generated-42,turns=8,density=0.5`.
+    ///
+    /// Any parameter not present in the name takes its default value. Names come from
+    /// clients, so ones with settings the generator can't build a map from are rejected.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut parts = name.strip_prefix(GENERATED_MAP_PREFIX)?.split(',');
+        let mut generator = Self::new(parts.next()?.parse().ok()?);
+
+        for part in parts {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "width" => generator.size.x = value.parse().ok()?,
+                "height" => generator.size.y = value.parse().ok()?,
+                "corridor" => generator.corridor_width = value.parse().ok()?,
+                "turns" => generator.turns = value.parse().ok()?,
+                "density" => generator.obstacle_density = value.parse().ok()?,
+                "gravity" => generator.gravity = value.parse().ok()?,
+                _ => return None,
+            }
+        }
+
+        generator.is_valid().then_some(generator)
+    }
+
+    /// Whether `generate` can build a map of a sensible size from these settings.
+    pub fn is_valid(&self) -> bool {
+        let size_ok = |size: f32| size.is_finite() && size > 0.0 && size <= MAX_GENERATED_MAP_SIZE;
+        let corridor_ok = self.corridor_width.is_finite() && self.corridor_width > 0.0;
+
+        size_ok(self.size.x)
+            && size_ok(self.size.y)
+            && corridor_ok
+            && (self.size.x / self.corridor_width) * (self.size.y / self.corridor_width)
+                <= MAX_GENERATED_MAP_CELLS
+            && self.obstacle_density.is_finite()
+            && (0.0..=1.0).contains(&self.obstacle_density)
+            && self.gravity.is_finite()
+    }
+
+    /// The map name for this generator, from which `from_name` recovers the same settings.
+    pub fn name(&self) -> String {
+        let defaults = Self::default();
+        let mut name = format!("{}{}", GENERATED_MAP_PREFIX, self.seed);
+
+        if self.size.x != defaults.size.x {
+            name.push_str(&format!(",width={}", self.size.x));
+        }
+        if self.size.y != defaults.size.y {
+            name.push_str(&format!(",height={}", self.size.y));
+        }
+        if self.corridor_width != defaults.corridor_width {
+            name.push_str(&format!(",corridor={}", self.corridor_width));
+        }
+        if self.turns != defaults.turns {
+            name.push_str(&format!(",turns={}", self.turns));
+        }
+        if self.obstacle_density != defaults.obstacle_density {
+            name.push_str(&format!(",density={}", self.obstacle_density));
+        }
+        if self.gravity != defaults.gravity {
+            name.push_str(&format!(",gravity={}", self.gravity));
+        }
+
+        name
+    }
+
+    pub fn generate(&self) -> Map {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let grid = TrackGrid::new(self.size, self.corridor_width);
+        let corridor = self.carve_corridor(&mut rng, &grid);
+
+        let mut in_corridor = vec![false; grid.cols * grid.rows];
+        for &(col, row) in &corridor {
+            in_corridor[row * grid.cols + col] = true;
+        }
+
+        // Fill the cells outside the corridor, merging horizontal runs of blocked
+        // cells into a single rectangular obstacle.
+        let density = self.obstacle_density.clamp(0.0, 1.0) as f64;
+        let mut obstacles = vec![];
+        for row in 0..grid.rows {
+            let mut run_start: Option<usize> = None;
+            for col in 0..=grid.cols {
+                let blocked = col < grid.cols
+                    && !in_corridor[row * grid.cols + col]
+                    && rng.random_bool(density);
+
+                match (blocked, run_start) {
+                    (true, None) => run_start = Some(col),
+                    (false, Some(start)) => {
+                        obstacles.push(grid.cells(start, col - 1, row));
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let (start_col, start_row) = corridor[0];
+        let (finish_col, finish_row) = corridor[corridor.len() - 1];
+
+        Map {
+            name: self.name(),
+            skin_path: None,
+            ship_path: None,
+            size: self.size,
+            gravity: self.gravity,
+            bounds: MapBounds::Walls,
+            obstacles,
+            start_regions: vec![grid.cells(start_col, start_col, start_row)],
+            finish_regions: vec![grid.cells(finish_col, finish_col, finish_row)],
+            gravity_wells: vec![],
+            force_zones: vec![],
+            boost_pads: vec![],
+            slow_zones: vec![],
+            fuel: None,
+            refuel_zones: vec![],
+            hull: Default::default(),
+            checkpoints: vec![],
+            respawn: Default::default(),
+            spawn_points: vec![],
+            ship_contact: Default::default(),
+        }
+    }
+
+    /// Returns the `(column, row)` cells of the corridor, in order from start to finish.
+    fn carve_corridor(&self, rng: &mut StdRng, grid: &TrackGrid) -> Vec<(usize, usize)> {
+        // The corridor alternates horizontal and vertical runs, always heading right so it
+        // never crosses itself. Each vertical run happens in its own column.
+        let mut vertical_runs = (self.turns as usize + 1) / 2;
+        if grid.rows < 2 {
+            vertical_runs = 0;
+        }
+        let vertical_runs = vertical_runs.min(grid.cols.saturating_sub(2));
+        let ends_horizontally =
+            self.turns % 2 == 0 || vertical_runs < (self.turns as usize + 1) / 2;
+
+        let mut turn_columns: Vec<usize> = (1..grid.cols - 1).collect();
+        turn_columns.shuffle(rng);
+        turn_columns.truncate(vertical_runs);
+        turn_columns.sort();
+
+        let mut col = 0;
+        let mut row = rng.random_range(0..grid.rows);
+        let mut corridor = vec![(col, row)];
+
+        for turn_column in turn_columns {
+            while col < turn_column {
+                col += 1;
+                corridor.push((col, row));
+            }
+
+            // Pick any other row to head to
+            let mut target_row = rng.random_range(0..grid.rows - 1);
+            if target_row >= row {
+                target_row += 1;
+            }
+            while row != target_row {
+                row = if target_row > row { row + 1 } else { row - 1 };
+                corridor.push((col, row));
+            }
+        }
+
+        if ends_horizontally {
+            while col < grid.cols - 1 {
+                col += 1;
+                corridor.push((col, row));
+            }
+        }
+
+        corridor
+    }
+}
+
+/// The grid of square cells a generated track is built from, centred on the map.
+struct TrackGrid {
+    cols: usize,
+    rows: usize,
+    cell_size: f32,
+    /// Top left corner of the grid in game coordinates
+    origin: Vec2,
+}
+
+impl TrackGrid {
+    fn new(map_size: Vec2, cell_size: f32) -> Self {
+        let cols = ((map_size.x / cell_size).floor() as usize).max(2);
+        let rows = ((map_size.y / cell_size).floor() as usize).max(1);
+        let grid_size = Vec2::new(cols as f32, rows as f32) * cell_size;
+
+        Self {
+            cols,
+            rows,
+            cell_size,
+            origin: Vec2::new(-grid_size.x / 2.0, grid_size.y / 2.0),
+        }
+    }
+
+    /// A rectangle covering the cells from `first_col` to `last_col` (inclusive) in `row`.
+    ///
+    /// Uses the same conventions as polygons loaded from Tiled: the position is the top
+    /// left corner, and the polygon is relative to it with Y pointing up and the first
+    /// point repeated at the end.
+    fn cells(&self, first_col: usize, last_col: usize, row: usize) -> VectorObject {
+        let width = (last_col - first_col + 1) as f32 * self.cell_size;
+        let height = self.cell_size;
+
+        VectorObject {
+            position: self.origin
+                + Vec2::new(
+                    first_col as f32 * self.cell_size,
+                    -(row as f32) * self.cell_size,
+                ),
+            polygon: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(width, 0.0),
+                Vec2::new(width, -height),
+                Vec2::new(0.0, -height),
+                Vec2::new(0.0, 0.0),
+            ],
+            ..Default::default()
+        }
+    }
+}
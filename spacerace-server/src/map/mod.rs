@@ -1,7 +1,9 @@
 use anyhow::anyhow;
 use bevy::prelude::Vec2;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Cursor;
+use std::path::Path;
 
 use bevy::{
     asset::{io::Reader, AssetLoader, LoadContext, LoadedFolder},
@@ -11,6 +13,17 @@ use bevy::{
 
 use crate::{app_state::AppState, game_logic::ServerState};
 
+mod export;
+mod features;
+pub mod generator;
+
+pub use features::{
+    BoostPad, ForceZone, FuelBudget, GravityWell, Hull, Motion, RefuelZone, RespawnRules, SlowZone,
+};
+pub use spacerace_protocol::ShipContact;
+
+use generator::MapGenerator;
+
 #[derive(Default)]
 pub struct MapAssetLoader;
 
@@ -28,15 +41,7 @@ impl AssetLoader for MapAssetLoader {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
 
-        let res_reader = MemoryReader { bytes };
-
-        // TODO: eliminate Loader's cache as bevy already as this covered
-        let mut loader = tiled::Loader::with_reader(res_reader);
-
-        // Note: the path here is only used for error reporting during parsing
-        let raw_map = loader.load_tmx_map(load_context.path())?;
-
-        let map = Map::new(raw_map)?;
+        let map = Map::from_tmx(load_context.path(), bytes)?;
         Ok(map)
     }
 
@@ -70,9 +75,36 @@ pub fn load_maps(asset_server: ResMut<AssetServer>, mut commands: Commands) {
     commands.insert_resource(MapsFolder(folder_handle));
 }
 
+/// Adds procedurally generated maps to the map rotation.
+///
+/// `SPACERACERS_GENERATED_MAPS` holds a comma separated list of seeds, e.g. `1,2,3`.
+pub fn register_generated_maps(app_state: Res<AppState>) {
+    let Ok(seeds) = std::env::var("SPACERACERS_GENERATED_MAPS") else {
+        return;
+    };
+
+    for seed in seeds.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match seed.parse::<u64>() {
+            Ok(seed) => {
+                let generator = MapGenerator::new(seed);
+                tracing::info!("generated map registered: {:?}", generator.name());
+                app_state.add_map(NamedMapId::generated(&generator));
+            }
+            Err(err) => tracing::warn!(?seed, ?err, "Ignoring invalid generated map seed"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NamedMapId(pub String, pub AssetId<Map>);
 
+impl NamedMapId {
+    /// Generated maps aren't assets, they are built from their name when a game starts.
+    pub fn generated(generator: &MapGenerator) -> Self {
+        NamedMapId(generator.name(), AssetId::default())
+    }
+}
+
 pub fn check_maps_loaded(
     app_state: Res<AppState>,
     mut next_state: ResMut<NextState<ServerState>>,
@@ -109,18 +141,85 @@ pub struct Map {
     pub ship_path: Option<String>,
     pub size: Vec2,
     pub gravity: f32,
+    #[serde(default)]
+    pub bounds: MapBounds,
     pub obstacles: Vec<VectorObject>,
     pub start_regions: Vec<VectorObject>,
     pub finish_regions: Vec<VectorObject>,
+    #[serde(default)]
+    pub gravity_wells: Vec<GravityWell>,
+    #[serde(default)]
+    pub force_zones: Vec<ForceZone>,
+    #[serde(default)]
+    pub boost_pads: Vec<BoostPad>,
+    #[serde(default)]
+    pub slow_zones: Vec<SlowZone>,
+    /// Ships have unlimited fuel when not set
+    #[serde(default)]
+    pub fuel: Option<FuelBudget>,
+    #[serde(default)]
+    pub refuel_zones: Vec<RefuelZone>,
+    #[serde(default)]
+    pub hull: Hull,
+    /// Regions ships respawn at once they have passed through them
+    #[serde(default)]
+    pub checkpoints: Vec<VectorObject>,
+    #[serde(default)]
+    pub respawn: RespawnRules,
+    /// Explicit ship start positions, used before any others inside the start regions
+    #[serde(default)]
+    pub spawn_points: Vec<Vec2>,
+    #[serde(default)]
+    pub ship_contact: ShipContact,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// What happens to ships reaching the edge of the map.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapBounds {
+    /// Solid walls surround the map
+    #[default]
+    Walls,
+    /// Ships leaving one side of the map reappear on the opposite side
+    Wrap,
+    /// Ships leaving the map are out of the race
+    Eliminate,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct VectorObject {
     pub position: Vec2,
     pub polygon: Vec<Vec2>,
+
+    // Optional physical properties set with Tiled custom properties on the object.
+    /// Bounciness of the collider, e.g. > 1.0 for a bumper
+    #[serde(default)]
+    pub restitution: Option<f32>,
+    #[serde(default)]
+    pub friction: Option<f32>,
+    /// Scales the damage ships take when hitting this object
+    #[serde(default)]
+    pub damage: Option<f32>,
+    /// Sensors don't block ships, they can fly straight through
+    #[serde(default)]
+    pub sensor: bool,
+    /// Set for obstacles that move or rotate during the race
+    #[serde(default)]
+    pub motion: Option<Motion>,
 }
 
 impl Map {
+    /// Parses a Tiled `.tmx` document.
+    ///
+    /// The path is only used for error reporting during parsing.
+    pub fn from_tmx(path: &Path, bytes: Vec<u8>) -> anyhow::Result<Map> {
+        // TODO: eliminate Loader's cache as bevy already as this covered
+        let mut loader = tiled::Loader::with_reader(MemoryReader { bytes });
+        let raw_map = loader.load_tmx_map(path)?;
+
+        Map::new(raw_map)
+    }
+
     fn new(raw_map: tiled::Map) -> anyhow::Result<Map> {
         let layer = raw_map
             .layers()
@@ -164,18 +263,125 @@ impl Map {
             })
             .unwrap_or(0.0f32);
 
+        let bounds = match raw_map.properties.get("bounds") {
+            Some(tiled::PropertyValue::StringValue(bounds)) => match bounds.as_str() {
+                "walls" => MapBounds::Walls,
+                "wrap" => MapBounds::Wrap,
+                "eliminate" => MapBounds::Eliminate,
+                _ => {
+                    tracing::warn!(?bounds, "Unknown map bounds, using walls");
+                    MapBounds::Walls
+                }
+            },
+            _ => MapBounds::Walls,
+        };
+
+        let fuel = float_property(&raw_map.properties, "fuel").map(|capacity| FuelBudget {
+            capacity,
+            thrust_burn: float_property(&raw_map.properties, "fuel_burn")
+                .unwrap_or(FuelBudget::DEFAULT_THRUST_BURN),
+            rotation_burn: float_property(&raw_map.properties, "rotation_fuel_burn")
+                .unwrap_or(FuelBudget::DEFAULT_ROTATION_BURN),
+        });
+
+        let hull = Hull {
+            health: float_property(&raw_map.properties, "hull").unwrap_or(Hull::DEFAULT_HEALTH),
+            damage_scale: float_property(&raw_map.properties, "damage_scale")
+                .unwrap_or(Hull::DEFAULT_DAMAGE_SCALE),
+        };
+
+        let respawn = RespawnRules {
+            enabled: bool_property(&raw_map.properties, "respawn").unwrap_or(false),
+            penalty: float_property(&raw_map.properties, "respawn_penalty")
+                .unwrap_or(RespawnRules::DEFAULT_PENALTY),
+        };
+
+        // Ships collide unless the map says otherwise, `contact_after` delays contact
+        let ship_contact = match raw_map.properties.get("ship_contact") {
+            Some(tiled::PropertyValue::StringValue(mode)) if mode == "ghost" => ShipContact::Ghost,
+            Some(tiled::PropertyValue::StringValue(mode)) if mode != "contact" => {
+                tracing::warn!(?mode, "Unknown ship contact mode, ships will collide");
+                ShipContact::Contact
+            }
+            _ => match float_property(&raw_map.properties, "contact_after") {
+                Some(seconds) => ShipContact::ContactAfter { seconds },
+                None => ShipContact::Contact,
+            },
+        };
+
         let mut map = Map {
             name: map_name,
             skin_path,
             ship_path,
             size: Vec2::new(map_width as f32, map_height as f32),
             gravity,
+            bounds,
             obstacles: vec![],
             finish_regions: vec![],
             start_regions: vec![],
+            gravity_wells: vec![],
+            force_zones: vec![],
+            boost_pads: vec![],
+            slow_zones: vec![],
+            fuel,
+            refuel_zones: vec![],
+            hull,
+            checkpoints: vec![],
+            respawn,
+            spawn_points: vec![],
+            ship_contact,
         };
 
+        // Polylines are paths for moving obstacles to follow, referenced by object id
+        let paths: HashMap<u32, Vec<Vec2>> = layer
+            .object_data()
+            .filter_map(|object| match &object.shape {
+                tiled::ObjectShape::Polyline { points } => Some((
+                    object.id(),
+                    points.iter().map(|&(x, y)| Vec2::new(x, -y)).collect(),
+                )),
+                _ => None,
+            })
+            .collect();
+
         for object in layer.object_data() {
+            let position = Vec2 {
+                x: object.x - (map_width as f32 / 2.0),
+                y: -(object.y) + (map_height as f32 / 2.0),
+            };
+
+            if object.user_type == "spawn" {
+                map.spawn_points.push(position);
+                continue;
+            }
+
+            if object.user_type == "gravity_well" {
+                // Wells are usually drawn as points or circles, use the centre of the shape
+                let centre = match object.shape {
+                    tiled::ObjectShape::Ellipse { width, height }
+                    | tiled::ObjectShape::Rect { width, height } => {
+                        position + Vec2::new(width / 2.0, -height / 2.0)
+                    }
+                    _ => position,
+                };
+                // A falloff of zero or less would make the pull infinite at the centre
+                let falloff = match float_property(&object.properties, "falloff") {
+                    Some(falloff) if !(falloff.is_finite() && falloff > 0.0) => {
+                        tracing::warn!(?falloff, "Invalid gravity well falloff, using default");
+                        GravityWell::DEFAULT_FALLOFF
+                    }
+                    falloff => falloff.unwrap_or(GravityWell::DEFAULT_FALLOFF),
+                };
+                map.gravity_wells.push(GravityWell {
+                    position: centre,
+                    strength: float_property(&object.properties, "strength")
+                        .filter(|strength| strength.is_finite())
+                        .unwrap_or(GravityWell::DEFAULT_STRENGTH),
+                    falloff,
+                });
+                continue;
+            }
+
             if let tiled::ObjectShape::Polygon { points } = &object.shape {
                 let mut polygon: Vec<Vec2> = points
                     .iter()
@@ -192,11 +398,13 @@ impl Map {
                 });
 
                 let map_object = VectorObject {
-                    position: Vec2 {
-                        x: object.x - (map_width as f32 / 2.0),
-                        y: -(object.y) + (map_height as f32 / 2.0),
-                    },
+                    position,
                     polygon,
+                    restitution: float_property(&object.properties, "restitution"),
+                    friction: float_property(&object.properties, "friction"),
+                    damage: float_property(&object.properties, "damage"),
+                    sensor: bool_property(&object.properties, "sensor").unwrap_or(false),
+                    motion: motion(&object.properties, &paths),
                 };
 
                 match object.user_type.as_str() {
@@ -207,6 +415,46 @@ impl Map {
                     "start" => {
                         map.start_regions.push(map_object);
                     }
+                    "boost" => {
+                        // Direction in degrees, boosting along the ship's heading when not set
+                        let direction = float_property(&object.properties, "direction")
+                            .map(|degrees| Vec2::from_angle(degrees.to_radians()));
+                        map.boost_pads.push(BoostPad {
+                            zone: map_object,
+                            strength: float_property(&object.properties, "strength")
+                                .unwrap_or(BoostPad::DEFAULT_STRENGTH),
+                            direction,
+                        });
+                    }
+                    "slow" => {
+                        map.slow_zones.push(SlowZone {
+                            zone: map_object,
+                            damping: float_property(&object.properties, "damping")
+                                .unwrap_or(SlowZone::DEFAULT_DAMPING),
+                        });
+                    }
+                    "checkpoint" => {
+                        map.checkpoints.push(map_object);
+                    }
+                    "refuel" => {
+                        map.refuel_zones.push(RefuelZone {
+                            zone: map_object,
+                            rate: float_property(&object.properties, "rate")
+                                .unwrap_or(RefuelZone::DEFAULT_RATE),
+                        });
+                    }
+                    "wind" | "current" => {
+                        // Direction in degrees, 0 pushes ships right and 90 pushes them up
+                        let direction = float_property(&object.properties, "direction")
+                            .unwrap_or(0.0)
+                            .to_radians();
+                        let strength = float_property(&object.properties, "strength")
+                            .unwrap_or(ForceZone::DEFAULT_STRENGTH);
+                        map.force_zones.push(ForceZone {
+                            zone: map_object,
+                            acceleration: Vec2::from_angle(direction) * strength,
+                        });
+                    }
                     // By default all other polygon objects are obstacles
                     _ => {
                         map.obstacles.push(map_object);
@@ -218,3 +466,51 @@ impl Map {
         Ok(map)
     }
 }
+
+/// Reads how an obstacle moves from its `path`, `speed` and `rotation_speed` properties.
+fn motion(properties: &tiled::Properties, paths: &HashMap<u32, Vec<Vec2>>) -> Option<Motion> {
+    let path = match properties.get("path") {
+        Some(tiled::PropertyValue::ObjectValue(id)) => match paths.get(id) {
+            Some(path) if !path.is_empty() => {
+                // Only the shape of the path matters, not where it was drawn
+                let start = path[0];
+                path.iter().map(|point| *point - start).collect()
+            }
+            _ => {
+                tracing::warn!(path_id = id, "Obstacle path must reference a polyline");
+                vec![]
+            }
+        },
+        _ => vec![],
+    };
+    // Rotation speed is given in degrees per second
+    let rotation_speed = float_property(properties, "rotation_speed").unwrap_or(0.0);
+
+    if path.is_empty() && rotation_speed == 0.0 {
+        return None;
+    }
+    Some(Motion {
+        path,
+        speed: float_property(properties, "speed").unwrap_or(0.0),
+        rotation_speed: rotation_speed.to_radians(),
+    })
+}
+
+/// Reads a numeric custom property, which may be typed as a float or int in Tiled.
+fn float_property(properties: &tiled::Properties, name: &str) -> Option<f32> {
+    match properties.get(name)? {
+        tiled::PropertyValue::FloatValue(value) => Some(*value),
+        tiled::PropertyValue::IntValue(value) => Some(*value as f32),
+        // Properties added without a type are strings
+        tiled::PropertyValue::StringValue(value) => value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn bool_property(properties: &tiled::Properties, name: &str) -> Option<bool> {
+    match properties.get(name)? {
+        tiled::PropertyValue::BoolValue(value) => Some(*value),
+        tiled::PropertyValue::StringValue(value) => value.parse().ok(),
+        _ => None,
+    }
+}
@@ -1,5 +1,8 @@
 use crate::app_state::AppState;
-use crate::network::{game_state_route, lobby_route, ship_control_route};
+use crate::network::{
+    control_sequence_route, game_state_route, lobby_route, map_route, respawn_route,
+    ship_control_route,
+};
 
 use axum::routing::{get, post};
 use axum::Router;
@@ -13,8 +16,22 @@ pub fn create_app(app_state: AppState) -> Router {
 
     Router::new()
         .route("/", get(root_handler))
-        .route("/lobby", post(lobby_route::lobby_handler))
+        .route(
+            "/lobby",
+            post(lobby_route::lobby_handler).get(lobby_route::lobby_status_handler),
+        )
         .route("/state", get(game_state_route::state_handler))
         .route("/control", post(ship_control_route::ship_control_handler))
+        .route(
+            "/control/sequence",
+            post(control_sequence_route::control_sequence_handler)
+                .delete(control_sequence_route::cancel_sequence_handler),
+        )
+        .route("/respawn", post(respawn_route::respawn_handler))
+        .route("/map", get(map_route::map_handler))
+        .route(
+            "/maps/generated/:name",
+            get(map_route::generated_map_handler),
+        )
         .with_state(app_state)
 }
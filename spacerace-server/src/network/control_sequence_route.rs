@@ -0,0 +1,111 @@
+use crate::app_state::AppState;
+use crate::control::{ScheduledInput, ShipInput};
+
+use axum::extract::State;
+use axum::Json;
+use spacerace_protocol::{CancelSequenceRequest, ControlSequenceRequest, ControlSequenceResponse};
+use std::ops::Deref;
+
+/// Most steps a player can have queued, including those appended to earlier sequences.
+const MAX_STEPS: usize = 1_000;
+
+/// Queues a sequence of inputs for the player's ship, each held for a number of ticks.
+///
+/// Inputs are clamped to [-1, 1] as for `/control`. Once the sequence finishes the ship
+/// coasts until it gets new input.
+#[axum::debug_handler]
+#[tracing::instrument(skip_all, fields(player.id, game.id))]
+pub async fn control_sequence_handler(
+    State(state): State<AppState>,
+    Json(request): Json<ControlSequenceRequest>,
+) -> Json<ControlSequenceResponse> {
+    let valid_steps = request
+        .steps
+        .iter()
+        .all(|step| step.thrust.is_finite() && step.rotation.is_finite() && step.ticks > 0);
+    if request.steps.is_empty() || request.steps.len() > MAX_STEPS || !valid_steps {
+        tracing::debug!("Rejecting invalid control sequence");
+        return Json(ControlSequenceResponse::error("invalid_input"));
+    }
+
+    let Some((game_state, player)) = state.get_active_player_by_password(&request.password) else {
+        return Json(ControlSequenceResponse::error("error"));
+    };
+    tracing::Span::current()
+        .record("player.id", &player.id.to_string().deref())
+        .record("game.id", game_state.game_id.to_string().deref());
+
+    let mut control_inputs = state.control_inputs.lock().unwrap();
+    let mut control_sequences = state.control_sequences.lock().unwrap();
+    let queue = control_sequences.entry(player.id).or_default();
+    let kept = if request.append { queue.len() } else { 0 };
+    if kept + request.steps.len() > MAX_STEPS {
+        tracing::debug!(
+            queued = kept,
+            "Rejecting control sequence, the queue is full"
+        );
+        return Json(ControlSequenceResponse::error("invalid_input"));
+    }
+
+    // Ticks already simulated can't be scheduled
+    let queue_end = match request.append {
+        true => queue.back().map_or(0, |step| step.end_tick),
+        false => 0,
+    };
+    let mut tick = request
+        .start_tick
+        .unwrap_or(0)
+        .max(game_state.tick + 1)
+        .max(queue_end);
+    // Schedule the whole sequence before touching the queue, so a rejected one leaves it be
+    let mut scheduled = Vec::with_capacity(request.steps.len());
+    for step in &request.steps {
+        let Some(end_tick) = tick.checked_add(step.ticks) else {
+            tracing::debug!("Rejecting control sequence running past the last tick");
+            return Json(ControlSequenceResponse::error("invalid_input"));
+        };
+        scheduled.push(ScheduledInput {
+            input: ShipInput {
+                thrust: step.thrust.clamp(-1.0, 1.0),
+                rotation: step.rotation.clamp(-1.0, 1.0),
+            },
+            start_tick: tick,
+            end_tick,
+        });
+        tick = end_tick;
+    }
+    if !request.append {
+        queue.clear();
+    }
+    queue.extend(scheduled);
+
+    // Coast once the sequence is over rather than going back to an old input
+    control_inputs.remove(&player.id);
+
+    tracing::debug!(steps = queue.len(), "Queued control sequence");
+    Json(ControlSequenceResponse {
+        status: "ok".to_string(),
+        start_tick: queue.front().map_or(tick, |step| step.start_tick),
+        end_tick: tick,
+    })
+}
+
+/// Drops the player's queued sequence, the ship coasts until it gets new input.
+#[axum::debug_handler]
+#[tracing::instrument(skip_all, fields(player.id))]
+pub async fn cancel_sequence_handler(
+    State(state): State<AppState>,
+    Json(request): Json<CancelSequenceRequest>,
+) -> Json<ControlSequenceResponse> {
+    let Some((game_state, player)) = state.get_active_player_by_password(&request.password) else {
+        return Json(ControlSequenceResponse::error("error"));
+    };
+    tracing::Span::current().record("player.id", &player.id.to_string().deref());
+
+    state.control_sequences.lock().unwrap().remove(&player.id);
+    Json(ControlSequenceResponse {
+        status: "ok".to_string(),
+        start_tick: game_state.tick,
+        end_tick: game_state.tick,
+    })
+}
@@ -1,33 +1,27 @@
 use crate::app_state::AppState;
-use crate::components::ship::Ship;
-use crate::game_state::{GameState, GameStatus};
+use crate::game_state::GameState;
 use axum::extract::{Query, State};
 use axum::Json;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use spacerace_protocol::{PublicGameState, StateResponse};
 use std::ops::Deref;
-use uuid::Uuid;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub enum StateResponse {
-    Inactive,
-    Active(PublicGameState),
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PublicGameState {
-    pub game_id: Uuid,
-    pub ships: Vec<Ship>,
-    pub map_name: String,
-    pub state: GameStatus,
-}
 
 impl From<&GameState> for PublicGameState {
     fn from(game: &GameState) -> Self {
         PublicGameState {
             game_id: game.game_id,
+            tick: game.tick,
             ships: game.ships.clone(),
+            obstacles: game.obstacles.clone(),
             map_name: game.map.name.clone(),
             state: game.state.clone(),
+            finish_times: game
+                .finish_times
+                .iter()
+                .map(|(player_id, time)| (*player_id, time - game.started_at))
+                .collect(),
+            did_not_finish: game.did_not_finish.iter().copied().collect(),
+            ship_contact: game.map.ship_contact,
         }
     }
 }
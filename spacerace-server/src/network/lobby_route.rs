@@ -1,11 +1,11 @@
 use crate::app_state::AppState;
-use crate::components::ship::Ship;
 use crate::components::{Player, PlayerRegistration};
 use crate::game_state::PendingGame;
 
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::Json;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use spacerace_protocol::{LobbyResponse, LobbyStatus};
 
 use rand::prelude::{IndexedRandom, SliceRandom};
 use std::time::Duration;
@@ -19,18 +19,19 @@ const LOBBY_WAIT_TIME: Duration = Duration::from_secs(30);
 // TODO improve to deal with responding to players queued waiting for game to start
 // TODO consider adding a countdown to the game starting...
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct LobbyResponse {
-    pub player_id: String,
-    pub game_id: String,
-    pub map: String,
-}
-
 #[axum::debug_handler]
 pub async fn lobby_handler(
     State(state): State<AppState>,
     Json(payload): Json<PlayerRegistration>,
 ) -> Json<LobbyResponse> {
+    Json(register_player(&state, payload))
+}
+
+/// Adds a player to the first pending game that suits them, creating one if none does.
+///
+/// Players registering over HTTP and local players joining on the server's display both
+/// come through here.
+pub fn register_player(state: &AppState, payload: PlayerRegistration) -> LobbyResponse {
     info!(?payload, "Request to add player to lobby");
     let player = Player::from(payload.clone());
 
@@ -42,32 +43,75 @@ pub async fn lobby_handler(
         .any(|g| g.players.iter().any(|p| p.password == player.password))
     {
         info!(player_id=?player.id, "Player is already registered in a pending game");
-        return Json(LobbyResponse {
+        return LobbyResponse {
             player_id: payload.name,
             game_id: "already_registered".to_string(),
             map: "already_registered".to_string(),
-        });
+            ship_id: None,
+        };
+    }
+
+    // Players may ask for a specific map, including a seeded generated track
+    let requested_map = match &payload.map {
+        Some(map_name) => match state.find_map(map_name) {
+            Some(map_id) => Some(map_id),
+            None => {
+                info!(?map_name, "Requested map not found");
+                return LobbyResponse {
+                    player_id: payload.name,
+                    game_id: "unknown_map".to_string(),
+                    map: map_name.clone(),
+                    ship_id: None,
+                };
+            }
+        },
+        None => None,
+    };
+    if let Some(ship_class) = &payload.ship_class {
+        if state.ship_classes.lock().unwrap().get(ship_class).is_none() {
+            info!(?ship_class, "Requested ship class not found");
+            return LobbyResponse {
+                player_id: payload.name,
+                game_id: "unknown_ship_class".to_string(),
+                map: payload.map.clone().unwrap_or_default(),
+                ship_id: None,
+            };
+        }
     }
 
-    // If no pending game exists or if they are all full, create a new one
-    if pending_games.len() == 0 || pending_games.iter().all(|g| g.players.len() >= MAX_PLAYERS) {
-        let map_ids = state.map_ids.lock().unwrap();
-        let random_map_ref = map_ids.choose(&mut rand::rng()).unwrap();
-        let random_map = random_map_ref.clone();
-        pending_games.push(PendingGame::new(random_map));
+    let is_joinable = |g: &PendingGame| {
+        g.players.len() < MAX_PLAYERS
+            && requested_map
+                .as_ref()
+                .map_or(true, |map_id| map_id.0 == g.map_id.0)
+            && payload
+                .ship_contact
+                .map_or(true, |contact| g.ship_contact == Some(contact))
+    };
+
+    // If no joinable pending game exists, create a new one
+    if !pending_games.iter().any(is_joinable) {
+        let map_id = requested_map.clone().unwrap_or_else(|| {
+            let map_ids = state.map_ids.lock().unwrap();
+            map_ids.choose(&mut rand::rng()).unwrap().clone()
+        });
+        let mut pending_game = PendingGame::new(map_id);
+        pending_game.ship_contact = payload.ship_contact;
+        pending_games.push(pending_game);
 
         info!("Creating a new pending game");
     }
 
     let pending_game = pending_games
         .iter_mut()
-        .filter(|g| g.players.len() < MAX_PLAYERS)
+        .filter(|g| is_joinable(g))
         .next()
         .unwrap();
 
     tracing::info!(player_id=?player.id, game_id=?pending_game.game_id, "Player will be added to pending game");
 
     // Add the player to the pending game
+    let player_id = player.id;
     pending_game.players.push(player);
 
     // Check if the pending game is now full
@@ -93,9 +137,43 @@ pub async fn lobby_handler(
     }
 
     // Respond with the lobby response
-    Json(LobbyResponse {
+    LobbyResponse {
         player_id: payload.name,
         game_id: pending_game.game_id.to_string(),
         map: pending_game.map_id.0.clone(),
-    })
+        ship_id: Some(player_id),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LobbyQuery {
+    pub game_id: String,
+}
+
+/// Tells a registered player where their game has got to, so clients can spot a game that
+/// was dropped, e.g. by a server restart, rather than wait for it forever.
+#[axum::debug_handler]
+pub async fn lobby_status_handler(
+    State(state): State<AppState>,
+    Query(query): Query<LobbyQuery>,
+) -> Json<LobbyStatus> {
+    // Lock in the same order as the game scheduler, which moves games from one to the other
+    let pending_games = state.lobby.lock().unwrap();
+    let active_game = state.active_game.lock().unwrap();
+
+    let status = if active_game
+        .as_ref()
+        .is_some_and(|game| game.game_id.to_string() == query.game_id)
+    {
+        LobbyStatus::Active
+    } else {
+        pending_games
+            .iter()
+            .position(|g| g.game_id.to_string() == query.game_id)
+            .map_or(LobbyStatus::Unknown, |ahead| LobbyStatus::Queued {
+                ahead,
+                players: pending_games[ahead].players.len(),
+            })
+    };
+    Json(status)
 }
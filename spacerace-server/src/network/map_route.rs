@@ -0,0 +1,53 @@
+use crate::app_state::AppState;
+use crate::map::generator::MapGenerator;
+use crate::map::Map;
+use crate::network::game_state_route::StateQuery;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use spacerace_protocol::MapResponse;
+
+/// The full geometry of the active game's map, so bots can plan their route.
+#[axum::debug_handler]
+#[tracing::instrument(skip(state))]
+pub async fn map_handler(
+    State(state): State<AppState>,
+    query: Option<Query<StateQuery>>,
+) -> Json<MapResponse<Map>> {
+    let active_game = state.active_game.lock().unwrap();
+    let Some(game) = active_game.as_ref() else {
+        tracing::debug!("map requested but no game running");
+        return Json(MapResponse::Inactive);
+    };
+
+    match query {
+        Some(query) if query.game_id != game.game_id.to_string() => {
+            tracing::debug!("Map requested for inactive game");
+            Json(MapResponse::Inactive)
+        }
+        _ => Json(MapResponse::Active(game.map.clone())),
+    }
+}
+
+/// Exports a generated map as a Tiled `.tmx` document, e.g. `/maps/generated/generated-42`.
+#[axum::debug_handler]
+#[tracing::instrument]
+pub async fn generated_map_handler(Path(name): Path<String>) -> impl IntoResponse {
+    match MapGenerator::from_name(&name) {
+        Some(generator) => {
+            let tmx = generator.generate().to_tmx();
+            (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, "application/xml")],
+                tmx,
+            )
+                .into_response()
+        }
+        None => {
+            tracing::debug!("not a generated map name");
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
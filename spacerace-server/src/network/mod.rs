@@ -7,9 +7,12 @@ use std::net::SocketAddr;
 use tracing::info;
 
 pub(crate) mod api;
+pub(crate) mod control_sequence_route;
 pub(crate) mod game_state_route;
 pub(crate) mod lobby_route;
-mod ship_control_route;
+pub(crate) mod map_route;
+pub(crate) mod respawn_route;
+pub(crate) mod ship_control_route;
 
 pub struct NetworkPlugin;
 
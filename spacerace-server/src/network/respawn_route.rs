@@ -0,0 +1,54 @@
+use crate::app_state::AppState;
+use crate::game_state::GameStatus;
+
+use axum::extract::State;
+use axum::Json;
+use spacerace_protocol::{RespawnRequest, RespawnResponse};
+use std::ops::Deref;
+
+/// Puts the player's ship back at its last checkpoint, for ships wedged somewhere they
+/// can't fly out of.
+#[axum::debug_handler]
+#[tracing::instrument(skip_all, fields(player.id, game.id))]
+pub async fn respawn_handler(
+    State(state): State<AppState>,
+    Json(request): Json<RespawnRequest>,
+) -> Json<RespawnResponse> {
+    let mut active_game_lock = state.active_game.lock().unwrap();
+    let Some(game) = active_game_lock.as_mut() else {
+        return Json(RespawnResponse {
+            status: "error".to_string(),
+            penalty: 0.0,
+        });
+    };
+    let player_id = game
+        .players
+        .iter()
+        .find(|player| player.password == request.password)
+        .map(|player| player.id);
+
+    // Ships that finished or are out of the race stay where they are
+    match player_id {
+        Some(player_id)
+            if game.state == GameStatus::Running
+                && !game.finish_times.contains_key(&player_id)
+                && !game.did_not_finish.contains(&player_id) =>
+        {
+            tracing::Span::current()
+                .record("player.id", &player_id.to_string().deref())
+                .record("game.id", game.game_id.to_string().deref());
+            tracing::debug!("Respawn requested");
+            if !game.respawn_requests.contains(&player_id) {
+                game.respawn_requests.push(player_id);
+            }
+            Json(RespawnResponse {
+                status: "ok".to_string(),
+                penalty: game.map.respawn.penalty,
+            })
+        }
+        _ => Json(RespawnResponse {
+            status: "error".to_string(),
+            penalty: 0.0,
+        }),
+    }
+}
@@ -4,30 +4,21 @@ use crate::control::ShipInput;
 
 use axum::extract::State;
 use axum::Json;
-use serde::{Deserialize, Serialize};
+use spacerace_protocol::{ControlInput, ShipControlResponse};
 use std::ops::Deref;
 
-#[derive(Deserialize)]
-pub struct ControlInput {
-    password: String,
-
-    // movement is either 0, or 1 for off, or full forward thrust
-    thrust: i8,
-    // Rotation is either -1, 0, or 1 for left, none, or right rotational thrust
-    rotation: i8,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ShipControlResponse {
-    pub status: String,
-}
-
 #[axum::debug_handler]
 #[tracing::instrument(skip_all, fields(player.id, player.name, player.input, game.id))]
 pub async fn ship_control_handler(
     State(state): State<AppState>,
     Json(input): Json<ControlInput>,
 ) -> Json<ShipControlResponse> {
+    // Infinite inputs can't be clamped to anything meaningful
+    if !input.thrust.is_finite() || !input.rotation.is_finite() {
+        tracing::debug!("Rejecting non-finite control input");
+        return Json(ShipControlResponse::error("invalid_input"));
+    }
+
     // 1. Validate the password and player existence in the given game retrieving the player uuid
     if let Some((game_state, player)) = state.get_active_player_by_password(&input.password) {
         tracing::Span::current()
@@ -35,29 +26,26 @@ pub async fn ship_control_handler(
             .record("player.name", &player.name.deref())
             .record("game.id", game_state.game_id.to_string().deref());
         // 2. If valid, update the `AppState.control_inputs` for this player
+        let ship_input = ShipInput {
+            thrust: input.thrust.clamp(-1.0, 1.0),
+            rotation: input.rotation.clamp(-1.0, 1.0),
+        };
         let mut control_inputs = state.control_inputs.lock().unwrap();
-        control_inputs.insert(
-            player.id,
-            ShipInput {
-                thrust: input.thrust.into(),
-                rotation: input.rotation.into(),
-            },
-        );
-        tracing::Span::current().record(
-            "player.input",
-            &format!("{:?}", control_inputs.get(&player.id)),
-        );
+        control_inputs.insert(player.id, ship_input.clone());
+        // The latest input wins over any sequence still queued
+        state.control_sequences.lock().unwrap().remove(&player.id);
+        tracing::Span::current().record("player.input", &format!("{:?}", ship_input));
         tracing::debug!(
-            input = &format!("{:?}", control_inputs.get(&player.id)),
+            input = &format!("{:?}", ship_input),
             "Updated control input"
         );
         Json(ShipControlResponse {
             status: "ok".to_string(),
+            thrust: ship_input.thrust,
+            rotation: ship_input.rotation,
         })
     } else {
         // 3. Respond with a `ShipControlResponse` indicating failure.
-        Json(ShipControlResponse {
-            status: "error".to_string(),
-        })
+        Json(ShipControlResponse::error("error"))
     }
 }
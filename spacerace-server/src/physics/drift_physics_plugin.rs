@@ -1,19 +1,18 @@
 use crate::app_state::AppState;
 use crate::components;
+use crate::game_logic::{eliminate_ship, ServerState};
+use crate::map::MapBounds;
 use bevy::app::{App, Plugin, PostUpdate, Startup, Update};
-use bevy::input::ButtonInput;
 use bevy::math::{Vec2, Vec3};
 use bevy::prelude::*;
-use bevy::prelude::{Commands, EventReader, KeyCode, Query, Res, Transform};
-use bevy_rapier2d::dynamics::{ExternalImpulse, RigidBody};
+use bevy::prelude::{Commands, EventReader, Query, Res, Transform};
+use bevy_rapier2d::dynamics::{Damping, ExternalForce, ReadMassProperties, RigidBody, Velocity};
 use bevy_rapier2d::pipeline::CollisionEvent::Started;
 use bevy_rapier2d::pipeline::{CollisionEvent, ContactForceEvent};
 use bevy_rapier2d::plugin::{NoUserData, RapierPhysicsPlugin};
 use bevy_rapier2d::rapier::prelude::Collider;
 use tracing::info;
 
-// TODO make these based on the map size
-
 pub fn setup_physics(mut commands: Commands) {}
 
 pub fn handle_collision_events(
@@ -46,7 +45,8 @@ pub fn handle_collision_events(
                 // Record the finish time
                 let mut active_game_lock = app_state.active_game.lock().unwrap();
                 if let Some(active_game) = active_game_lock.as_mut() {
-                    let current_time = time.elapsed_secs();
+                    let penalty = active_game.penalties.get(&player.id).unwrap_or(&0.0);
+                    let current_time = time.elapsed_secs() + penalty;
                     active_game
                         .finish_times
                         .entry(player.id)
@@ -60,6 +60,127 @@ pub fn handle_collision_events(
     }
 }
 
+/// Records the checkpoint each ship passed through most recently.
+pub fn handle_checkpoint_events(
+    mut collision_events: EventReader<CollisionEvent>,
+    checkpoint_query: Query<&components::CheckpointRegion>,
+    ship_query: Query<&components::ship::ControllableShip>,
+    app_state: Res<AppState>,
+) {
+    for collision_event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = collision_event else {
+            continue;
+        };
+
+        let (ship, checkpoint) = if let Ok(ship) = ship_query.get(*entity1) {
+            (ship, checkpoint_query.get(*entity2))
+        } else if let Ok(ship) = ship_query.get(*entity2) {
+            (ship, checkpoint_query.get(*entity1))
+        } else {
+            continue;
+        };
+        let Ok(checkpoint) = checkpoint else {
+            continue;
+        };
+
+        let mut active_game_lock = app_state.active_game.lock().unwrap();
+        if let Some(active_game) = active_game_lock.as_mut() {
+            tracing::debug!(player.id=?ship.id, checkpoint = checkpoint.index, "Reached checkpoint");
+            active_game
+                .checkpoints_reached
+                .insert(ship.id, checkpoint.index);
+        }
+    }
+}
+
+/// Boosts ships crossing a boost pad, and slows ships down while they're inside a slow zone.
+pub fn handle_boost_and_slow_events(
+    mut collision_events: EventReader<CollisionEvent>,
+    boost_query: Query<&components::BoostRegion>,
+    slow_query: Query<&components::SlowRegion>,
+    mut ship_query: Query<
+        (&Transform, &mut Velocity, &mut Damping),
+        With<components::ship::ControllableShip>,
+    >,
+) {
+    for collision_event in collision_events.read() {
+        let (entity1, entity2, started) = match collision_event {
+            CollisionEvent::Started(entity1, entity2, _) => (*entity1, *entity2, true),
+            CollisionEvent::Stopped(entity1, entity2, _) => (*entity1, *entity2, false),
+        };
+
+        let (ship_entity, region_entity) = if ship_query.contains(entity1) {
+            (entity1, entity2)
+        } else if ship_query.contains(entity2) {
+            (entity2, entity1)
+        } else {
+            continue;
+        };
+        let Ok((transform, mut velocity, mut damping)) = ship_query.get_mut(ship_entity) else {
+            continue;
+        };
+
+        if let Ok(boost) = boost_query.get(region_entity) {
+            if started {
+                let direction = boost
+                    .direction
+                    .unwrap_or_else(|| (transform.rotation * Vec3::Y).truncate());
+                velocity.linvel += direction * boost.strength;
+            }
+        } else if let Ok(slow) = slow_query.get(region_entity) {
+            // Zones may overlap, so add and remove each zone's damping as the ship
+            // enters and leaves it
+            if started {
+                damping.linear_damping += slow.damping;
+            } else {
+                damping.linear_damping -= slow.damping;
+            }
+        }
+    }
+}
+
+/// Damages ships by the contact forces they take, eliminating ships whose hull gives out.
+pub fn apply_hull_damage_system(
+    mut contact_force_events: EventReader<ContactForceEvent>,
+    mut ship_query: Query<(
+        &components::ship::ControllableShip,
+        &mut components::ship::Health,
+    )>,
+    damage_query: Query<&components::Damage>,
+    mut commands: Commands,
+    app_state: Res<AppState>,
+) {
+    let mut active_game_lock = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_lock.as_mut() else {
+        return;
+    };
+    let hull = active_game.map.hull.clone();
+
+    for event in contact_force_events.read() {
+        for (ship_entity, other_entity) in [
+            (event.collider1, event.collider2),
+            (event.collider2, event.collider1),
+        ] {
+            let Ok((ship, mut health)) = ship_query.get_mut(ship_entity) else {
+                continue;
+            };
+            // Already eliminated by an earlier contact this frame
+            if health.0 <= 0.0 {
+                continue;
+            }
+
+            let multiplier = damage_query
+                .get(other_entity)
+                .map_or(1.0, |damage| damage.0);
+            health.0 -= hull.damage(event.total_force_magnitude, multiplier);
+            if health.0 <= 0.0 {
+                health.0 = 0.0;
+                eliminate_ship(&mut commands, ship_entity, ship, active_game);
+            }
+        }
+    }
+}
+
 pub struct DriftPhysicsPlugin;
 
 impl Plugin for DriftPhysicsPlugin {
@@ -68,85 +189,80 @@ impl Plugin for DriftPhysicsPlugin {
 
         app.add_systems(Startup, setup_physics);
 
-        #[cfg(feature = "ui")]
-        {
-            app.add_systems(Update, apply_keyboard_controls_system);
-        }
-
-        app.add_systems(Update, apply_bounds_system);
+        app.add_systems(
+            Update,
+            apply_bounds_system.run_if(in_state(ServerState::Active)),
+        );
+        app.add_systems(
+            Update,
+            apply_force_fields_system.run_if(in_state(ServerState::Active)),
+        );
         app.add_systems(PostUpdate, handle_collision_events);
+        app.add_systems(PostUpdate, handle_boost_and_slow_events);
+        app.add_systems(PostUpdate, handle_checkpoint_events);
+        app.add_systems(
+            PostUpdate,
+            apply_hull_damage_system.run_if(in_state(ServerState::Active)),
+        );
     }
 }
 
+/// Wraps or eliminates ships leaving the map, depending on the map's bounds.
+///
+/// Maps with walls don't need this, their edges are colliders spawned with the scene.
 pub fn apply_bounds_system(
-    mut player_info: Query<(&components::ship::ControllableShip, &Sprite, &mut Transform)>,
+    mut commands: Commands,
+    mut ships: Query<(Entity, &components::ship::ControllableShip, &mut Transform)>,
     app_state: Res<AppState>,
 ) {
-    let active_game_guard = app_state.active_game.lock().unwrap();
-    if let Some(active_game) = active_game_guard.as_ref() {
-        // The map is centered at (0,0), with `map.size` specifying total width & height.
-        // So half of that is the "max" in each axis direction.
-        let half_map_width = active_game.map.size.x / 2.0;
-        let half_map_height = active_game.map.size.y / 2.0;
-
-        for (player, sprite, mut transform) in &mut player_info {
-            // Suppose the ship sprite is, for example, 25.0 x 25.0.
-            // This ensures the entire sprite is clamped on-screen.
-            // If `custom_size` isn’t set, you can fallback to a default radius or just skip it.
-            let half_ship_width = match sprite.custom_size {
-                Some(size) => size.x / 2.0,
-                None => 0.0, // or a fallback radius
-            };
-            let half_ship_height = match sprite.custom_size {
-                Some(size) => size.y / 2.0,
-                None => 0.0,
-            };
+    let mut active_game_guard = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_guard.as_mut() else {
+        return;
+    };
 
-            // Calculate the min and max coordinates
-            // so the entire sprite stays inside the map.
-            let min_x = -half_map_width + half_ship_width;
-            let max_x = half_map_width - half_ship_width;
-            let min_y = -half_map_height + half_ship_height;
-            let max_y = half_map_height - half_ship_height;
+    // The map is centered at (0,0), with `map.size` specifying total width & height.
+    let map_size = active_game.map.size;
+    let half_map_size = map_size / 2.0;
+    let bounds = active_game.map.bounds;
 
-            // Now clamp each axis
-            transform.translation.x = transform.translation.x.clamp(min_x, max_x);
-            transform.translation.y = transform.translation.y.clamp(min_y, max_y);
+    for (entity, ship, mut transform) in &mut ships {
+        let position = transform.translation.truncate();
+        if position.abs().cmple(half_map_size).all() {
+            continue;
+        }
 
-            // Z should remain unchanged, so no clamp for transform.translation.z
+        match bounds {
+            MapBounds::Walls => {}
+            MapBounds::Wrap => {
+                // Move the ship to the opposite edge, keeping its velocity
+                let wrapped = (position + half_map_size).rem_euclid(map_size) - half_map_size;
+                transform.translation.x = wrapped.x;
+                transform.translation.y = wrapped.y;
+            }
+            MapBounds::Eliminate => {
+                eliminate_ship(&mut commands, entity, ship, active_game);
+            }
         }
     }
 }
 
-pub fn apply_keyboard_controls_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut app_state: Res<AppState>,
-    mut player_info: Query<(
-        &components::ship::ControllableShip,
-        &mut Transform,
-        &mut ExternalImpulse,
-    )>,
+/// Applies the map's gravity wells and wind/current zones to each ship as a force.
+pub fn apply_force_fields_system(
+    mut ships: Query<
+        (&Transform, &ReadMassProperties, &mut ExternalForce),
+        With<components::ship::ControllableShip>,
+    >,
+    app_state: Res<AppState>,
 ) {
-    for (player, mut transform, mut rb_imps) in &mut player_info {
-        let up = keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]);
-        let down = keyboard_input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]);
-        let left = keyboard_input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]);
-        let right = keyboard_input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]);
-
-        // Vector "forwards" if up is pressed, "backwards" if down is pressed
-        let thrust = (up as i8) - (down as i8);
-        let rotation = (left as i8) - (right as i8);
-
-        // Inject the player's input into the physics simulation as if it had come
-        // via the network.
-        let mut control_inputs_lock = app_state.control_inputs.lock().unwrap();
-        let player_uuid = player.id.clone();
-        control_inputs_lock.insert(
-            player_uuid,
-            crate::control::ShipInput {
-                thrust: thrust as f32,
-                rotation: rotation as f32,
-            },
-        );
+    let active_game_guard = app_state.active_game.lock().unwrap();
+    let Some(active_game) = active_game_guard.as_ref() else {
+        return;
+    };
+
+    for (transform, mass_properties, mut external_force) in &mut ships {
+        let acceleration = active_game
+            .map
+            .force_field_at(transform.translation.truncate());
+        external_force.force = acceleration * mass_properties.get().mass;
     }
 }
@@ -1,3 +1,3 @@
-mod drift_physics_plugin;
+pub(crate) mod drift_physics_plugin;
 
 pub use drift_physics_plugin::DriftPhysicsPlugin;
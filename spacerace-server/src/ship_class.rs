@@ -0,0 +1,190 @@
+use anyhow::ensure;
+use bevy::prelude::{Res, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::AppState;
+
+/// How a class of ship handles, letting players trade agility for speed.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ShipClass {
+    pub name: String,
+    /// Mass added to the collider's own, in kg
+    pub mass: f32,
+    /// Impulse applied along the ship's heading at full thrust
+    pub impulse: f32,
+    /// Torque impulse applied at full rotation
+    pub torque_impulse: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub collider: ShipCollider,
+}
+
+/// Collider shape of a ship class, in pixels.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum ShipCollider {
+    Ball {
+        radius: f32,
+    },
+    /// A capsule along the ship's heading
+    Capsule {
+        half_length: f32,
+        radius: f32,
+    },
+    Cuboid {
+        half_width: f32,
+        half_height: f32,
+    },
+}
+
+impl ShipClass {
+    /// Checks the class can be simulated, as classes can come from a config file.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let values = [
+            self.mass,
+            self.impulse,
+            self.torque_impulse,
+            self.linear_damping,
+            self.angular_damping,
+        ];
+        ensure!(
+            values.iter().all(|value| value.is_finite()),
+            "ship class {:?} has a value that isn't a number",
+            self.name
+        );
+        ensure!(
+            values.iter().all(|value| *value >= 0.0),
+            "ship class {:?} has a negative mass, impulse or damping",
+            self.name
+        );
+
+        let dimensions: &[f32] = match &self.collider {
+            ShipCollider::Ball { radius } => &[*radius],
+            ShipCollider::Capsule {
+                half_length,
+                radius,
+            } => &[*half_length, *radius],
+            ShipCollider::Cuboid {
+                half_width,
+                half_height,
+            } => &[*half_width, *half_height],
+        };
+        ensure!(
+            dimensions
+                .iter()
+                .all(|size| size.is_finite() && *size > 0.0),
+            "ship class {:?} has a collider without a positive size",
+            self.name
+        );
+        Ok(())
+    }
+}
+
+impl ShipCollider {
+    /// Width and height of the box the shape fits in.
+    pub fn size(&self) -> Vec2 {
+        match *self {
+            ShipCollider::Ball { radius } => Vec2::splat(radius * 2.0),
+            ShipCollider::Capsule {
+                half_length,
+                radius,
+            } => Vec2::new(radius * 2.0, (half_length + radius) * 2.0),
+            ShipCollider::Cuboid {
+                half_width,
+                half_height,
+            } => Vec2::new(half_width, half_height) * 2.0,
+        }
+    }
+}
+
+/// The ship classes players can pick from at registration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ShipClasses(pub Vec<ShipClass>);
+
+impl Default for ShipClasses {
+    fn default() -> Self {
+        Self(vec![
+            ShipClass {
+                name: "balanced".to_string(),
+                mass: 200.0,
+                impulse: 8_000.0,
+                torque_impulse: 8_000.0,
+                linear_damping: 0.2,
+                angular_damping: 0.5,
+                collider: ShipCollider::Ball { radius: 12.5 },
+            },
+            ShipClass {
+                name: "light".to_string(),
+                mass: 120.0,
+                impulse: 6_000.0,
+                torque_impulse: 7_000.0,
+                linear_damping: 0.3,
+                angular_damping: 0.8,
+                collider: ShipCollider::Ball { radius: 10.0 },
+            },
+            ShipClass {
+                name: "heavy".to_string(),
+                mass: 320.0,
+                impulse: 14_000.0,
+                torque_impulse: 9_000.0,
+                linear_damping: 0.1,
+                angular_damping: 0.4,
+                collider: ShipCollider::Capsule {
+                    half_length: 8.0,
+                    radius: 11.0,
+                },
+            },
+        ])
+    }
+}
+
+impl ShipClasses {
+    /// Reads ship classes from JSON, rejecting an empty list or any class that can't be
+    /// simulated.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let classes: Self = serde_json::from_str(json)?;
+        ensure!(!classes.0.is_empty(), "no ship classes configured");
+        for class in &classes.0 {
+            class.validate()?;
+        }
+        Ok(classes)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ShipClass> {
+        self.0.iter().find(|class| class.name == name)
+    }
+
+    /// The class of ships whose players didn't pick one, the first configured.
+    pub fn default_class(&self) -> &ShipClass {
+        &self.0[0]
+    }
+
+    /// The class a player picked, falling back to the default class.
+    pub fn for_player(&self, name: Option<&str>) -> &ShipClass {
+        name.and_then(|name| self.get(name))
+            .unwrap_or_else(|| self.default_class())
+    }
+}
+
+/// Replaces the built-in ship classes with those in the JSON file named by
+/// `SPACERACERS_SHIP_CLASSES`, if set.
+pub fn load_ship_classes(app_state: Res<AppState>) {
+    let Ok(path) = std::env::var("SPACERACERS_SHIP_CLASSES") else {
+        return;
+    };
+
+    let classes = std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| ShipClasses::from_json(&json));
+    match classes {
+        Ok(classes) => {
+            tracing::info!(?path, count = classes.0.len(), "Loaded ship classes");
+            *app_state.ship_classes.lock().unwrap() = classes;
+        }
+        Err(err) => tracing::warn!(
+            ?path,
+            ?err,
+            "Failed to load ship classes, using the built-in ones"
+        ),
+    }
+}
@@ -0,0 +1,205 @@
+use crate::app_state::AppState;
+use crate::components::Player;
+use crate::control::{scheduled_input, ScheduledInput, ShipInput};
+use crate::game_state::GameState;
+use crate::map::generator::MapGenerator;
+use crate::network::control_sequence_route::{cancel_sequence_handler, control_sequence_handler};
+use axum::body::Body;
+use axum::http::Request;
+use axum::routing::post;
+use http_body_util::BodyExt;
+use spacerace_protocol::ControlSequenceResponse;
+use std::collections::VecDeque;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn step(thrust: f32, start_tick: u64, end_tick: u64) -> ScheduledInput {
+    ScheduledInput {
+        input: ShipInput {
+            thrust,
+            rotation: 0.0,
+        },
+        start_tick,
+        end_tick,
+    }
+}
+
+#[test]
+fn test_scheduled_input_follows_the_queue() {
+    let mut queue = VecDeque::from([step(1.0, 10, 50), step(-1.0, 50, 60)]);
+    let thrust_at = |queue: &mut VecDeque<ScheduledInput>, tick| {
+        scheduled_input(queue, tick).map(|input| input.thrust)
+    };
+
+    assert_eq!(thrust_at(&mut queue, 5), None);
+    assert_eq!(thrust_at(&mut queue, 10), Some(1.0));
+    assert_eq!(thrust_at(&mut queue, 49), Some(1.0));
+    assert_eq!(thrust_at(&mut queue, 50), Some(-1.0));
+    assert_eq!(queue.len(), 1);
+    assert_eq!(thrust_at(&mut queue, 60), None);
+    assert!(queue.is_empty());
+}
+
+async fn send(
+    app: &axum::Router,
+    method: &str,
+    body: serde_json::Value,
+) -> ControlSequenceResponse {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri("/control/sequence")
+                .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_control_sequence_endpoint() {
+    let app_state = AppState::new();
+    let player = Player::new("Pilot".to_string(), None, "secret".to_string());
+    let mut game = GameState::new(
+        Uuid::new_v4(),
+        vec![player.clone()],
+        MapGenerator::new(1).generate(),
+    )
+    .unwrap();
+    game.tick = 100;
+    *app_state.active_game.lock().unwrap() = Some(game);
+
+    let app = axum::Router::new()
+        .route(
+            "/control/sequence",
+            post(control_sequence_handler).delete(cancel_sequence_handler),
+        )
+        .with_state(app_state.clone());
+
+    // Thrust for 40 ticks then rotate for 10, starting on the next tick
+    let response = send(
+        &app,
+        "POST",
+        serde_json::json!({
+            "password": "secret",
+            "steps": [
+                {"thrust": 1, "rotation": 0, "ticks": 40},
+                {"thrust": 0, "rotation": -5, "ticks": 10}
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(response.status, "ok");
+    assert_eq!((response.start_tick, response.end_tick), (101, 151));
+
+    // Appended steps follow on from the queue
+    let response = send(
+        &app,
+        "POST",
+        serde_json::json!({
+            "password": "secret",
+            "append": true,
+            "steps": [{"thrust": -1, "rotation": 0, "ticks": 5}]
+        }),
+    )
+    .await;
+    assert_eq!((response.start_tick, response.end_tick), (101, 156));
+    {
+        let sequences = app_state.control_sequences.lock().unwrap();
+        let queue = &sequences[&player.id];
+        assert_eq!(queue.len(), 3);
+        // Out of range inputs are clamped
+        assert_eq!(queue[1].input.rotation, -1.0);
+    }
+
+    // Steps must last at least one tick
+    let response = send(
+        &app,
+        "POST",
+        serde_json::json!({
+            "password": "secret",
+            "steps": [{"thrust": 1, "rotation": 0, "ticks": 0}]
+        }),
+    )
+    .await;
+    assert_eq!(response.status, "invalid_input");
+
+    // Sequences running past the last tick are rejected, leaving the queue as it was
+    let response = send(
+        &app,
+        "POST",
+        serde_json::json!({
+            "password": "secret",
+            "append": true,
+            "start_tick": u64::MAX,
+            "steps": [{"thrust": 1, "rotation": 0, "ticks": 1}]
+        }),
+    )
+    .await;
+    assert_eq!(response.status, "invalid_input");
+    let response = send(
+        &app,
+        "POST",
+        serde_json::json!({
+            "password": "secret",
+            "append": true,
+            "steps": [
+                {"thrust": 1, "rotation": 0, "ticks": u64::MAX - 200},
+                {"thrust": 1, "rotation": 0, "ticks": u64::MAX - 200}
+            ]
+        }),
+    )
+    .await;
+    assert_eq!(response.status, "invalid_input");
+    assert_eq!(
+        app_state.control_sequences.lock().unwrap()[&player.id].len(),
+        3
+    );
+
+    let response = send(&app, "DELETE", serde_json::json!({"password": "secret"})).await;
+    assert_eq!(response.status, "ok");
+    assert!(!app_state
+        .control_sequences
+        .lock()
+        .unwrap()
+        .contains_key(&player.id));
+}
+
+#[tokio::test]
+async fn test_control_sequence_queue_length_is_limited() {
+    let app_state = AppState::new();
+    let player = Player::new("Pilot".to_string(), None, "secret".to_string());
+    let game = GameState::new(
+        Uuid::new_v4(),
+        vec![player.clone()],
+        MapGenerator::new(1).generate(),
+    )
+    .unwrap();
+    *app_state.active_game.lock().unwrap() = Some(game);
+
+    let app = axum::Router::new()
+        .route("/control/sequence", post(control_sequence_handler))
+        .with_state(app_state.clone());
+
+    let steps = vec![serde_json::json!({"thrust": 1, "rotation": 0, "ticks": 1}); 600];
+    let append =
+        |append| serde_json::json!({"password": "secret", "append": append, "steps": steps});
+
+    assert_eq!(send(&app, "POST", append(true)).await.status, "ok");
+    // Appending would take the queue past its limit, even though each request is in it
+    assert_eq!(
+        send(&app, "POST", append(true)).await.status,
+        "invalid_input"
+    );
+    assert_eq!(
+        app_state.control_sequences.lock().unwrap()[&player.id].len(),
+        600
+    );
+    // Replacing the queue is fine
+    assert_eq!(send(&app, "POST", append(false)).await.status, "ok");
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::{ButtonInput, GamepadAxis, GamepadButton, KeyCode};
+
+use crate::app_state::AppState;
+use crate::control::local_input::{
+    BoundAxis, BoundButton, BoundKey, InputBindings, InputDevice, LocalPlayers,
+};
+use crate::map::NamedMapId;
+
+#[test]
+fn test_bindings_load_from_json() {
+    let bindings: InputBindings = serde_json::from_str(
+        r#"{
+            "keyboards": [
+                {"name": "Left", "thrust": ["KeyT"], "reverse": ["KeyG"], "left": ["KeyF"], "right": ["KeyH"]}
+            ],
+            "gamepad": {"thrust": ["South", "RightTrigger2"], "steering": "RightStickX"}
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(bindings.keyboards.len(), 1);
+    assert_eq!(bindings.keyboards[0].name, "Left");
+    assert_eq!(bindings.keyboards[0].thrust, [BoundKey(KeyCode::KeyT)]);
+    assert_eq!(
+        bindings.gamepad.thrust,
+        [
+            BoundButton(GamepadButton::South),
+            BoundButton(GamepadButton::RightTrigger2)
+        ]
+    );
+    assert_eq!(
+        bindings.gamepad.steering,
+        Some(BoundAxis(GamepadAxis::RightStickX))
+    );
+    // Anything left out keeps its default
+    assert_eq!(bindings.gamepad.join, InputBindings::default().gamepad.join);
+
+    let unknown = serde_json::from_str::<InputBindings>(
+        r#"{"keyboards": [{"name": "Bad", "thrust": ["KeyW"], "reverse": ["Nope"], "left": [], "right": []}]}"#,
+    );
+    assert!(unknown
+        .unwrap_err()
+        .to_string()
+        .contains("unknown input \"Nope\""));
+}
+
+#[test]
+fn test_keyboard_layouts_fly_independently() {
+    let bindings = InputBindings::default();
+    let (wasd, arrows) = (&bindings.keyboards[0], &bindings.keyboards[1]);
+    let mut keyboard = ButtonInput::<KeyCode>::default();
+    keyboard.press(KeyCode::KeyW);
+    keyboard.press(KeyCode::KeyA);
+    keyboard.press(KeyCode::ArrowUp);
+    keyboard.press(KeyCode::ArrowDown);
+    keyboard.press(KeyCode::ArrowRight);
+
+    // Thrust and rotation combine
+    let input = wasd.input(&keyboard);
+    assert_eq!((input.thrust, input.rotation), (1.0, 1.0));
+    // Opposite keys cancel out, and one layout's keys don't reach the other
+    let input = arrows.input(&keyboard);
+    assert_eq!((input.thrust, input.rotation), (0.0, -1.0));
+}
+
+#[test]
+fn test_local_players_join_the_lobby_with_their_device() {
+    let app_state = AppState::new();
+    app_state.add_map(NamedMapId("some_map".to_string(), Default::default()));
+    let mut local_players = LocalPlayers::default();
+
+    let wasd = local_players
+        .join(&app_state, InputDevice::Keyboard(0), "WASD")
+        .unwrap();
+    let arrows = local_players
+        .join(&app_state, InputDevice::Keyboard(1), "Arrows")
+        .unwrap();
+    // A device flies one ship at a time
+    assert_eq!(
+        local_players.join(&app_state, InputDevice::Keyboard(0), "WASD"),
+        None
+    );
+
+    assert_ne!(wasd, arrows);
+    assert_eq!(local_players.player(InputDevice::Keyboard(0)), Some(wasd));
+    {
+        let lobby = app_state.lobby.lock().unwrap();
+        let players = &lobby[0].players;
+        assert_eq!(players.len(), 2);
+        assert_eq!((players[0].id, players[0].name.as_str()), (wasd, "WASD"));
+        assert_eq!(players[1].team.as_deref(), Some("Local"));
+    }
+
+    // Players stay assigned while waiting, and let go of their device once they're done
+    local_players.release_finished(&app_state);
+    assert_eq!(local_players.assignments().len(), 2);
+    app_state.lobby.lock().unwrap().clear();
+    local_players.release_finished(&app_state);
+    assert!(local_players.assignments().is_empty());
+}
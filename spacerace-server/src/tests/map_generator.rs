@@ -0,0 +1,170 @@
+use crate::map::generator::MapGenerator;
+use crate::map::{Map, VectorObject};
+use bevy::prelude::Vec2;
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+fn centre(object: &VectorObject) -> Vec2 {
+    let (min, max) = bounds(object);
+    (min + max) / 2.0
+}
+
+fn bounds(object: &VectorObject) -> (Vec2, Vec2) {
+    let points = object.polygon.iter().map(|p| object.position + *p);
+    let min = points.clone().reduce(Vec2::min).unwrap();
+    let max = points.reduce(Vec2::max).unwrap();
+    (min, max)
+}
+
+fn is_blocked(map: &Map, point: Vec2) -> bool {
+    map.obstacles.iter().any(|obstacle| {
+        let (min, max) = bounds(obstacle);
+        point.cmpgt(min).all() && point.cmplt(max).all()
+    })
+}
+
+/// Walks the generator's grid from the start region to the finish region, only stepping
+/// between cell centres that aren't covered by an obstacle.
+fn has_clear_path(map: &Map, cell_size: f32) -> bool {
+    let start = centre(&map.start_regions[0]);
+    let finish = centre(&map.finish_regions[0]);
+    let half_size = map.size / 2.0;
+
+    let to_cell = |point: Vec2| ((point - start) / cell_size).round().as_ivec2();
+    let goal = to_cell(finish);
+
+    let mut visited = HashSet::from([to_cell(start)]);
+    let mut queue = VecDeque::from([to_cell(start)]);
+    while let Some(cell) = queue.pop_front() {
+        if cell == goal {
+            return true;
+        }
+        for step in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = cell + bevy::math::IVec2::from(step);
+            let point = start + next.as_vec2() * cell_size;
+            if point.abs().cmplt(half_size).all() && !is_blocked(map, point) && visited.insert(next)
+            {
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn test_generated_map_is_deterministic() {
+    let generator = MapGenerator::new(42);
+
+    let first = generator.generate();
+    let second = generator.generate();
+    assert_eq!(first.to_tmx(), second.to_tmx());
+
+    let other = MapGenerator::new(43).generate();
+    assert_ne!(first.to_tmx(), other.to_tmx());
+}
+
+#[test]
+fn test_generated_map_always_has_a_path_to_the_finish() {
+    for seed in 0..20 {
+        let generator = MapGenerator {
+            seed,
+            turns: seed as u32 % 10,
+            obstacle_density: 1.0,
+            ..Default::default()
+        };
+        let map = generator.generate();
+
+        assert!(!map.obstacles.is_empty());
+        assert!(
+            has_clear_path(&map, generator.corridor_width),
+            "no path through {}",
+            map.name
+        );
+    }
+}
+
+#[test]
+fn test_generated_map_name_round_trip() {
+    let generator = MapGenerator {
+        seed: 7,
+        size: Vec2::new(2400.0, 1200.0),
+        turns: 3,
+        obstacle_density: 0.25,
+        gravity: -4.5,
+        ..Default::default()
+    };
+
+    let name = generator.name();
+    assert_eq!(
+        name,
+        "generated-7,width=2400,height=1200,turns=3,density=0.25,gravity=-4.5"
+    );
+    assert_eq!(MapGenerator::from_name(&name), Some(generator));
+
+    assert_eq!(
+        MapGenerator::from_name("generated-7"),
+        Some(MapGenerator::new(7))
+    );
+    assert_eq!(MapGenerator::from_name("Aga"), None);
+    assert_eq!(MapGenerator::from_name("generated-7,colour=red"), None);
+}
+
+#[test]
+fn test_generated_map_name_with_unusable_settings_is_rejected() {
+    for name in [
+        "generated-7,corridor=0",
+        "generated-7,corridor=-120",
+        "generated-7,corridor=NaN",
+        "generated-7,corridor=0.001",
+        "generated-7,density=NaN",
+        "generated-7,density=1.5",
+        "generated-7,density=-0.1",
+        "generated-7,width=0",
+        "generated-7,width=inf",
+        "generated-7,width=1000000",
+        "generated-7,height=-1080",
+        "generated-7,height=1e30",
+        "generated-7,gravity=NaN",
+        "generated-7,gravity=-inf",
+    ] {
+        assert_eq!(MapGenerator::from_name(name), None, "{name} was accepted");
+    }
+
+    assert!(MapGenerator::from_name("generated-7,corridor=60,density=0,width=10000").is_some());
+}
+
+#[test]
+fn test_generated_map_tmx_export_round_trip() {
+    let map = MapGenerator::new(3).generate();
+
+    let loaded = Map::from_tmx(Path::new("generated.tmx"), map.to_tmx().into_bytes()).unwrap();
+
+    assert_eq!(loaded.name, map.name);
+    assert_eq!(loaded.size, map.size);
+    assert_eq!(loaded.obstacles.len(), map.obstacles.len());
+    assert_eq!(loaded.start_regions.len(), 1);
+    assert_eq!(loaded.finish_regions.len(), 1);
+    for (loaded, original) in loaded.obstacles.iter().zip(&map.obstacles) {
+        assert!(loaded.position.abs_diff_eq(original.position, 0.01));
+        assert_eq!(loaded.polygon.len(), original.polygon.len());
+    }
+}
+
+#[test]
+fn test_map_endpoint_json_decodes_as_client_geometry() {
+    use spacerace_protocol::MapResponse;
+
+    let map = MapGenerator::new(5).generate();
+    let json = serde_json::to_string(&MapResponse::Active(map.clone())).unwrap();
+
+    let MapResponse::Active(geometry) = serde_json::from_str::<MapResponse>(&json).unwrap() else {
+        panic!("expected an active map");
+    };
+    assert_eq!(geometry.name, map.name);
+    assert_eq!(geometry.size, (map.size.x, map.size.y));
+    assert_eq!(geometry.obstacles.len(), map.obstacles.len());
+
+    let finish = &map.finish_regions[0];
+    let centre = finish.position + finish.centre();
+    assert!(geometry.finish_regions[0].contains((centre.x, centre.y)));
+}
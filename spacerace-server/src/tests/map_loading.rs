@@ -0,0 +1,425 @@
+use crate::map::{FuelBudget, Hull, Map, ShipContact};
+use bevy::prelude::Vec2;
+use std::path::Path;
+
+const BUMPER_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <properties>
+  <property name="name" value="Bumpers"/>
+ </properties>
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="start" x="100" y="100">
+   <polygon points="0,0 50,0 50,50 0,50"/>
+  </object>
+  <object id="2" x="500" y="400">
+   <properties>
+    <property name="restitution" type="float" value="1.5"/>
+    <property name="damage" type="int" value="2"/>
+   </properties>
+   <polygon points="0,0 40,0 40,40 0,40"/>
+  </object>
+  <object id="3" x="700" y="400">
+   <properties>
+    <property name="friction" value="3"/>
+    <property name="sensor" type="bool" value="true"/>
+   </properties>
+   <polygon points="0,0 40,0 40,40 0,40"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_object_physical_properties() {
+    let map = Map::from_tmx(Path::new("bumpers.tmx"), BUMPER_MAP.as_bytes().to_vec()).unwrap();
+
+    assert_eq!(map.name, "Bumpers");
+    assert_eq!(map.start_regions.len(), 1);
+    assert_eq!(map.obstacles.len(), 2);
+
+    let bumper = &map.obstacles[0];
+    assert_eq!(bumper.restitution, Some(1.5));
+    assert_eq!(bumper.damage, Some(2.0));
+    assert_eq!(bumper.friction, None);
+    assert!(!bumper.sensor);
+
+    // Untyped properties are read from their string value
+    let soft_zone = &map.obstacles[1];
+    assert_eq!(soft_zone.friction, Some(3.0));
+    assert!(soft_zone.sensor);
+
+    // Properties survive exporting back to TMX
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.obstacles[0].restitution, Some(1.5));
+    assert_eq!(exported.obstacles[1].friction, Some(3.0));
+    assert!(exported.obstacles[1].sensor);
+}
+
+const FORCE_FIELD_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="gravity_well" x="500" y="400">
+   <properties>
+    <property name="strength" type="float" value="100"/>
+    <property name="falloff" type="float" value="200"/>
+   </properties>
+   <point/>
+  </object>
+  <object id="2" type="current" x="0" y="0">
+   <properties>
+    <property name="direction" type="float" value="90"/>
+    <property name="strength" type="float" value="30"/>
+   </properties>
+   <polygon points="0,0 1000,0 1000,200 0,200"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_gravity_wells_and_force_zones() {
+    let map = Map::from_tmx(Path::new("forces.tmx"), FORCE_FIELD_MAP.as_bytes().to_vec()).unwrap();
+
+    assert_eq!(map.gravity_wells.len(), 1);
+    assert_eq!(map.force_zones.len(), 1);
+    assert_eq!(map.obstacles.len(), 0);
+
+    // The well is in the centre of the map, at half strength one falloff distance away
+    let acceleration = map.force_field_at(Vec2::new(200.0, 0.0));
+    assert!(acceleration.abs_diff_eq(Vec2::new(-50.0, 0.0), 0.001));
+
+    // The current runs along the top of the map, pushing ships up
+    let acceleration = map.force_field_at(Vec2::new(0.0, 350.0))
+        - map.gravity_wells[0].acceleration_at(Vec2::new(0.0, 350.0));
+    assert!(acceleration.abs_diff_eq(Vec2::new(0.0, 30.0), 0.001));
+
+    // Force fields survive exporting back to TMX
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.gravity_wells[0].falloff, 200.0);
+    assert!(exported.force_zones[0]
+        .acceleration
+        .abs_diff_eq(Vec2::new(0.0, 30.0), 0.001));
+}
+
+const BAD_FALLOFF_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="gravity_well" x="500" y="400">
+   <properties>
+    <property name="falloff" type="float" value="0"/>
+   </properties>
+   <point/>
+  </object>
+  <object id="2" type="gravity_well" x="200" y="400">
+   <properties>
+    <property name="falloff" type="float" value="-50"/>
+    <property name="strength" value="NaN"/>
+   </properties>
+   <point/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_gravity_well_with_invalid_falloff_uses_default() {
+    use crate::map::GravityWell;
+
+    let map = Map::from_tmx(
+        Path::new("falloff.tmx"),
+        BAD_FALLOFF_MAP.as_bytes().to_vec(),
+    )
+    .unwrap();
+
+    for well in &map.gravity_wells {
+        assert_eq!(well.falloff, GravityWell::DEFAULT_FALLOFF);
+        assert!(well.strength.is_finite());
+        // Right on top of a well there's no direction to pull in
+        assert_eq!(well.acceleration_at(well.position), Vec2::ZERO);
+    }
+    assert!(map.force_field_at(Vec2::new(10.0, 0.0)).is_finite());
+}
+
+const BOOST_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="boost" x="100" y="100">
+   <properties>
+    <property name="strength" type="float" value="450"/>
+    <property name="direction" type="float" value="90"/>
+   </properties>
+   <polygon points="0,0 50,0 50,50 0,50"/>
+  </object>
+  <object id="2" type="boost" x="300" y="100">
+   <polygon points="0,0 50,0 50,50 0,50"/>
+  </object>
+  <object id="3" type="slow" x="500" y="400">
+   <properties>
+    <property name="damping" value="2.5"/>
+   </properties>
+   <polygon points="0,0 100,0 100,100 0,100"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_boost_pads_and_slow_zones() {
+    use crate::map::BoostPad;
+
+    let map = Map::from_tmx(Path::new("boost.tmx"), BOOST_MAP.as_bytes().to_vec()).unwrap();
+
+    assert_eq!(map.boost_pads.len(), 2);
+    assert_eq!(map.slow_zones.len(), 1);
+    assert!(map.obstacles.is_empty());
+
+    let pad = &map.boost_pads[0];
+    assert_eq!(pad.strength, 450.0);
+    assert!(pad.direction.unwrap().abs_diff_eq(Vec2::Y, 0.001));
+    // Without a direction the pad boosts along the ship's heading
+    assert_eq!(map.boost_pads[1].strength, BoostPad::DEFAULT_STRENGTH);
+    assert_eq!(map.boost_pads[1].direction, None);
+
+    assert_eq!(map.slow_zones[0].damping, 2.5);
+    assert!(map.slow_zones[0].zone.contains(Vec2::new(50.0, -50.0)));
+
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.boost_pads.len(), 2);
+    assert_eq!(exported.boost_pads[0].strength, 450.0);
+    assert!(exported.boost_pads[0]
+        .direction
+        .unwrap()
+        .abs_diff_eq(Vec2::Y, 0.001));
+    assert_eq!(exported.boost_pads[1].direction, None);
+    assert_eq!(exported.slow_zones[0].damping, 2.5);
+}
+
+const MOVING_OBSTACLE_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="path" x="200" y="200">
+   <polyline points="0,0 100,0 100,100"/>
+  </object>
+  <object id="2" x="500" y="400">
+   <properties>
+    <property name="path" type="object" value="1"/>
+    <property name="speed" type="float" value="50"/>
+   </properties>
+   <polygon points="0,0 40,0 40,40 0,40"/>
+  </object>
+  <object id="3" x="700" y="400">
+   <properties>
+    <property name="rotation_speed" type="float" value="90"/>
+   </properties>
+   <polygon points="0,0 40,0 40,40 0,40"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_moving_obstacles() {
+    let map = Map::from_tmx(
+        Path::new("moving.tmx"),
+        MOVING_OBSTACLE_MAP.as_bytes().to_vec(),
+    )
+    .unwrap();
+
+    // The path itself isn't an obstacle
+    assert_eq!(map.obstacles.len(), 2);
+
+    let slider = map.obstacles[0].motion.as_ref().unwrap();
+    assert_eq!(
+        slider.path,
+        vec![Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, -100.0)]
+    );
+    assert_eq!(slider.offset_at(0.0), Vec2::ZERO);
+    assert_eq!(slider.offset_at(1.0), Vec2::new(50.0, 0.0));
+    assert_eq!(slider.offset_at(3.0), Vec2::new(100.0, -50.0));
+    // Heads back along the path once it reaches the end
+    assert_eq!(slider.offset_at(6.0), Vec2::new(100.0, 0.0));
+    assert_eq!(slider.offset_at(8.0), Vec2::ZERO);
+
+    let spinner = map.obstacles[1].motion.as_ref().unwrap();
+    assert_eq!(spinner.offset_at(10.0), Vec2::ZERO);
+    assert!((spinner.rotation_at(2.0) - std::f32::consts::PI).abs() < 1e-5);
+    assert_eq!(map.obstacles[1].centre(), Vec2::new(20.0, -20.0));
+
+    // Motion survives exporting back to TMX
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.obstacles.len(), 2);
+    assert_eq!(
+        exported.obstacles[0].motion.as_ref().unwrap().path,
+        slider.path
+    );
+    assert_eq!(exported.obstacles[0].motion.as_ref().unwrap().speed, 50.0);
+    assert!(
+        (exported.obstacles[1]
+            .motion
+            .as_ref()
+            .unwrap()
+            .rotation_speed
+            - spinner.rotation_speed)
+            .abs()
+            < 1e-5
+    );
+}
+
+const FUEL_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <properties>
+  <property name="fuel" type="float" value="100"/>
+  <property name="fuel_burn" type="int" value="20"/>
+ </properties>
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="refuel" x="500" y="400">
+   <properties>
+    <property name="rate" type="float" value="50"/>
+   </properties>
+   <polygon points="0,0 100,0 100,100 0,100"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_fuel_budget_and_refuel_zones() {
+    let map = Map::from_tmx(Path::new("fuel.tmx"), FUEL_MAP.as_bytes().to_vec()).unwrap();
+
+    let fuel = map.fuel.as_ref().unwrap();
+    assert_eq!(fuel.capacity, 100.0);
+    assert_eq!(fuel.thrust_burn, 20.0);
+    assert_eq!(fuel.rotation_burn, FuelBudget::DEFAULT_ROTATION_BURN);
+    // Full thrust while turning for half a second
+    assert_eq!(fuel.burn(1.0, -1.0, 0.5), 11.0);
+    assert_eq!(fuel.burn(0.0, 0.0, 0.5), 0.0);
+
+    assert_eq!(map.refuel_zones.len(), 1);
+    assert_eq!(map.refuel_zones[0].rate, 50.0);
+    assert!(map.obstacles.is_empty());
+    assert!(map.refuel_zones[0].zone.contains(Vec2::new(50.0, -50.0)));
+
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.fuel.unwrap().thrust_burn, 20.0);
+    assert_eq!(exported.refuel_zones[0].rate, 50.0);
+
+    // Maps without the property have unlimited fuel
+    let unlimited =
+        Map::from_tmx(Path::new("bumpers.tmx"), BUMPER_MAP.as_bytes().to_vec()).unwrap();
+    assert!(unlimited.fuel.is_none());
+}
+
+const HULL_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <properties>
+  <property name="hull" type="float" value="50"/>
+  <property name="damage_scale" type="float" value="0.001"/>
+ </properties>
+ <objectgroup id="1" name="Objects">
+  <object id="1" x="500" y="400">
+   <properties>
+    <property name="damage" type="float" value="3"/>
+   </properties>
+   <polygon points="0,0 40,0 40,40 0,40"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_hull_damage() {
+    let map = Map::from_tmx(Path::new("hull.tmx"), HULL_MAP.as_bytes().to_vec()).unwrap();
+    assert_eq!(map.hull.health, 50.0);
+    assert_eq!(map.hull.damage_scale, 0.001);
+    assert_eq!(map.obstacles[0].damage, Some(3.0));
+
+    // Gentle contacts are free, harder hits scale with the obstacle's damage multiplier
+    let hull = Hull::default();
+    assert_eq!(hull.damage(Hull::SAFE_CONTACT_FORCE / 2.0, 1.0), 0.0);
+    let hit = hull.damage(Hull::SAFE_CONTACT_FORCE + 100_000.0, 1.0);
+    assert!(hit > 0.0);
+    assert_eq!(
+        hull.damage(Hull::SAFE_CONTACT_FORCE + 100_000.0, 2.0),
+        2.0 * hit
+    );
+
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.hull.health, 50.0);
+
+    let default_hull =
+        Map::from_tmx(Path::new("bumpers.tmx"), BUMPER_MAP.as_bytes().to_vec()).unwrap();
+    assert_eq!(default_hull.hull.health, Hull::DEFAULT_HEALTH);
+}
+
+const CHECKPOINT_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <properties>
+  <property name="respawn" type="bool" value="true"/>
+  <property name="respawn_penalty" type="float" value="10"/>
+ </properties>
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="checkpoint" x="500" y="400">
+   <polygon points="0,0 100,0 100,100 0,100"/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+#[test]
+fn test_checkpoints_and_respawn_rules() {
+    let map = Map::from_tmx(
+        Path::new("checkpoint.tmx"),
+        CHECKPOINT_MAP.as_bytes().to_vec(),
+    )
+    .unwrap();
+
+    assert_eq!(map.checkpoints.len(), 1);
+    assert!(map.obstacles.is_empty());
+    assert!(map.respawn.enabled);
+    assert_eq!(map.respawn.penalty, 10.0);
+
+    let exported = Map::from_tmx(Path::new("exported.tmx"), map.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.checkpoints.len(), 1);
+    assert!(exported.respawn.enabled);
+    assert_eq!(exported.respawn.penalty, 10.0);
+
+    // Without the property eliminated ships don't finish, as before respawning existed
+    let no_respawn =
+        Map::from_tmx(Path::new("bumpers.tmx"), BUMPER_MAP.as_bytes().to_vec()).unwrap();
+    assert!(!no_respawn.respawn.enabled);
+}
+
+#[test]
+fn test_ship_contact_modes() {
+    let map_with = |properties: &str| {
+        let tmx = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <properties>
+  {properties}
+ </properties>
+ <objectgroup id="1" name="Objects"/>
+</map>
+"#
+        );
+        Map::from_tmx(Path::new("contact.tmx"), tmx.into_bytes()).unwrap()
+    };
+
+    let ghost = map_with(r#"<property name="ship_contact" value="ghost"/>"#);
+    assert_eq!(ghost.ship_contact, ShipContact::Ghost);
+    assert!(!ghost.ship_contact.ships_collide(100.0));
+
+    let delayed = map_with(r#"<property name="contact_after" type="float" value="10"/>"#);
+    assert_eq!(
+        delayed.ship_contact,
+        ShipContact::ContactAfter { seconds: 10.0 }
+    );
+    assert!(!delayed.ship_contact.ships_collide(9.0));
+    assert!(delayed.ship_contact.ships_collide(10.0));
+
+    let exported = Map::from_tmx(Path::new("exported.tmx"), delayed.to_tmx().into_bytes()).unwrap();
+    assert_eq!(exported.ship_contact, delayed.ship_contact);
+
+    let contact = map_with(r#"<property name="name" value="Bumper cars"/>"#);
+    assert_eq!(contact.ship_contact, ShipContact::Contact);
+}
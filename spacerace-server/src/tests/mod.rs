@@ -1,9 +1,25 @@
+#[cfg(test)]
+mod control_sequences;
+#[cfg(all(test, feature = "ui"))]
+mod local_input;
+#[cfg(test)]
+mod map_generator;
+#[cfg(test)]
+mod map_loading;
+#[cfg(test)]
+mod obstacle_collisions;
+#[cfg(test)]
+mod ship_classes;
+#[cfg(test)]
+mod spawn_allocation;
+
 #[cfg(test)]
 mod tests {
     use crate::app_state::AppState;
     use crate::map::{Map, NamedMapId};
     use crate::network::api::root_handler;
-    use crate::network::lobby_route::{lobby_handler, LobbyResponse};
+    use crate::network::lobby_route::lobby_handler;
+    use spacerace_protocol::LobbyResponse;
 
     use crate::components::PlayerRegistration;
     use axum::routing::{get, post};
@@ -44,6 +60,9 @@ mod tests {
             name: "TestPlayer".to_string(),
             team: Some("TestTeam".to_string()),
             password: "secret".to_string(),
+            map: None,
+            ship_class: None,
+            ship_contact: None,
         };
 
         let response = app
@@ -65,6 +84,8 @@ mod tests {
 
         assert_eq!(lobby_response.player_id, "TestPlayer");
         assert_eq!(lobby_response.map, "some_map");
+        let lobby = app_state.lobby.lock().unwrap();
+        assert_eq!(lobby_response.ship_id, Some(lobby[0].players[0].id));
         // assert that the response game ID is a valid UUID
         assert!(Uuid::parse_str(&lobby_response.game_id).is_ok());
     }
@@ -82,12 +103,18 @@ mod tests {
             name: "Player1".to_string(),
             team: Some("TeamA".to_string()),
             password: "secret1".to_string(),
+            map: None,
+            ship_class: None,
+            ship_contact: None,
         };
 
         let player2 = PlayerRegistration {
             name: "Player2".to_string(),
             team: Some("TeamA".to_string()),
             password: "secret2".to_string(),
+            map: None,
+            ship_class: None,
+            ship_contact: None,
         };
 
         let response1 = app
@@ -143,4 +170,237 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_respawn_endpoint() {
+        use crate::components::Player;
+        use crate::game_state::{GameState, GameStatus};
+        use crate::map::generator::MapGenerator;
+        use crate::network::respawn_route::respawn_handler;
+        use spacerace_protocol::RespawnResponse;
+
+        let app_state = AppState::new();
+        let player = Player::new("Stuck".to_string(), None, "secret".to_string());
+        let mut game = GameState::new(
+            Uuid::new_v4(),
+            vec![player.clone()],
+            MapGenerator::new(1).generate(),
+        )
+        .unwrap();
+        game.state = GameStatus::Running;
+        *app_state.active_game.lock().unwrap() = Some(game);
+
+        let app = axum::Router::new()
+            .route("/respawn", post(respawn_handler))
+            .with_state(app_state.clone());
+
+        for (password, status) in [("secret", "ok"), ("wrong", "error")] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/respawn")
+                        .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                        .body(Body::from(
+                            serde_json::json!({ "password": password }).to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let respawn: RespawnResponse = serde_json::from_slice(&body_bytes).unwrap();
+            assert_eq!(respawn.status, status);
+        }
+
+        let active_game = app_state.active_game.lock().unwrap();
+        assert_eq!(
+            active_game.as_ref().unwrap().respawn_requests,
+            vec![player.id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lobby_status_endpoint() {
+        use crate::components::Player;
+        use crate::game_state::{GameState, PendingGame};
+        use crate::map::generator::MapGenerator;
+        use crate::network::lobby_route::lobby_status_handler;
+        use spacerace_protocol::LobbyStatus;
+
+        let app_state = AppState::new();
+        let active =
+            GameState::new(Uuid::new_v4(), vec![], MapGenerator::new(1).generate()).unwrap();
+        let active_id = active.game_id;
+        *app_state.active_game.lock().unwrap() = Some(active);
+        let map_id = NamedMapId("some_map".to_string(), Default::default());
+        let (first, mut second) = (PendingGame::new(map_id.clone()), PendingGame::new(map_id));
+        second.players = vec![
+            Player::new("One".to_string(), None, "secret1".to_string()),
+            Player::new("Two".to_string(), None, "secret2".to_string()),
+        ];
+        let second_id = second.game_id;
+        app_state.lobby.lock().unwrap().extend([first, second]);
+
+        let app = axum::Router::new()
+            .route("/lobby", get(lobby_status_handler))
+            .with_state(app_state.clone());
+
+        for (game_id, expected) in [
+            (active_id, LobbyStatus::Active),
+            (
+                second_id,
+                LobbyStatus::Queued {
+                    ahead: 1,
+                    players: 2,
+                },
+            ),
+            (Uuid::new_v4(), LobbyStatus::Unknown),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/lobby?game_id={game_id}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let status: LobbyStatus = serde_json::from_slice(&body_bytes).unwrap();
+            assert_eq!(status, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lobby_ship_class() {
+        let app_state = AppState::new();
+        app_state.add_map(NamedMapId("some_map".to_string(), Default::default()));
+
+        let app = axum::Router::new()
+            .route("/lobby", post(lobby_handler))
+            .with_state(app_state.clone());
+
+        for (password, ship_class, game_id_is_uuid) in
+            [("secret1", "heavy", true), ("secret2", "submarine", false)]
+        {
+            let player = PlayerRegistration {
+                name: "Pilot".to_string(),
+                team: None,
+                password: password.to_string(),
+                map: None,
+                ship_class: Some(ship_class.to_string()),
+                ship_contact: None,
+            };
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/lobby")
+                        .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                        .body(Body::from(serde_json::to_string(&player).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let lobby_response: LobbyResponse = serde_json::from_slice(&body_bytes).unwrap();
+            assert_eq!(
+                Uuid::parse_str(&lobby_response.game_id).is_ok(),
+                game_id_is_uuid
+            );
+            if !game_id_is_uuid {
+                assert_eq!(lobby_response.game_id, "unknown_ship_class");
+                // No map was asked for, and the ship class isn't one
+                assert_eq!(lobby_response.map, "");
+            }
+        }
+
+        let lobby = app_state.lobby.lock().unwrap();
+        let players = &lobby[0].players;
+        assert_eq!(players.len(), 1);
+        assert_eq!(players[0].ship_class.as_deref(), Some("heavy"));
+    }
+
+    #[tokio::test]
+    async fn test_control_inputs_are_clamped() {
+        use crate::components::Player;
+        use crate::game_state::GameState;
+        use crate::map::generator::MapGenerator;
+        use crate::network::ship_control_route::ship_control_handler;
+        use spacerace_protocol::ShipControlResponse;
+
+        let app_state = AppState::new();
+        let player = Player::new("Pilot".to_string(), None, "secret".to_string());
+        let game = GameState::new(
+            Uuid::new_v4(),
+            vec![player.clone()],
+            MapGenerator::new(1).generate(),
+        )
+        .unwrap();
+        *app_state.active_game.lock().unwrap() = Some(game);
+
+        let app = axum::Router::new()
+            .route("/control", post(ship_control_handler))
+            .with_state(app_state.clone());
+
+        for (body, status, thrust, rotation) in [
+            (
+                r#"{"password": "secret", "thrust": 0.5, "rotation": -0.25}"#,
+                "ok",
+                0.5,
+                -0.25,
+            ),
+            (
+                r#"{"password": "secret", "thrust": 127, "rotation": -15}"#,
+                "ok",
+                1.0,
+                -1.0,
+            ),
+            (
+                r#"{"password": "secret", "thrust": 1e39, "rotation": 0}"#,
+                "invalid_input",
+                0.0,
+                0.0,
+            ),
+            (
+                r#"{"password": "wrong", "thrust": 1, "rotation": 0}"#,
+                "error",
+                0.0,
+                0.0,
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/control")
+                        .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+            let control: ShipControlResponse = serde_json::from_slice(&body_bytes).unwrap();
+            assert_eq!(control.status, status, "for {body}");
+            assert_eq!((control.thrust, control.rotation), (thrust, rotation));
+        }
+
+        // Only the accepted, clamped input is applied
+        let control_inputs = app_state.control_inputs.lock().unwrap();
+        let input = control_inputs.get(&player.id).unwrap();
+        assert_eq!((input.thrust, input.rotation), (1.0, -1.0));
+    }
 }
@@ -0,0 +1,284 @@
+use crate::components::ship::ControllableShip;
+use crate::components::{BoostRegion, SlowRegion};
+use crate::game_logic::{boundary_walls, polygon_collider, ship_physics, spawn_ship};
+use crate::map::generator::MapGenerator;
+use crate::map::{ShipContact, VectorObject};
+use crate::physics::drift_physics_plugin::handle_boost_and_slow_events;
+use crate::ship_class::ShipClasses;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use uuid::Uuid;
+
+/// A headless app running just the physics simulation, stepping 1/60th of a second per update.
+fn physics_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(50.0),
+    ));
+    app.insert_resource(TimestepMode::Fixed {
+        dt: 1.0 / 60.0,
+        substeps: 1,
+    });
+
+    // Let the plugin create its context, then turn gravity off
+    app.update();
+    let world = app.world_mut();
+    let mut configs = world.query::<&mut RapierConfiguration>();
+    for mut config in configs.iter_mut(world) {
+        config.gravity = Vec2::ZERO;
+    }
+    app
+}
+
+fn rectangle(position: Vec2, width: f32, height: f32) -> VectorObject {
+    VectorObject {
+        position,
+        polygon: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(width, 0.0),
+            Vec2::new(width, -height),
+            Vec2::new(0.0, -height),
+            Vec2::new(0.0, 0.0),
+        ],
+        ..Default::default()
+    }
+}
+
+/// Launches a ship from the origin along the X axis, returning where it is after two seconds.
+fn fly_ship(mut app: App, speed: f32) -> Vec3 {
+    let ship = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            ship_physics(ShipClasses::default().default_class(), true),
+        ))
+        .insert(Velocity::linear(Vec2::new(speed, 0.0)))
+        .id();
+
+    for _ in 0..120 {
+        app.update();
+    }
+
+    app.world().get::<Transform>(ship).unwrap().translation
+}
+
+fn fly_ship_into(obstacle: &VectorObject, speed: f32) -> Vec3 {
+    let mut app = physics_app();
+    app.world_mut().spawn((
+        Transform::from_xyz(obstacle.position.x, obstacle.position.y, 0.0),
+        polygon_collider(obstacle),
+    ));
+
+    fly_ship(app, speed)
+}
+
+#[test]
+fn test_fast_ship_cannot_tunnel_through_thin_obstacle() {
+    // The ship covers ~80 pixels per step, much more than the wall is thick
+    let wall = rectangle(Vec2::new(200.0, 200.0), 10.0, 400.0);
+
+    let position = fly_ship_into(&wall, 5_000.0);
+
+    assert!(
+        position.x < 200.0,
+        "ship ended up at {position:?}, beyond the wall"
+    );
+}
+
+#[test]
+fn test_fast_ship_cannot_get_inside_obstacle() {
+    let block = rectangle(Vec2::new(200.0, 200.0), 300.0, 400.0);
+
+    let position = fly_ship_into(&block, 5_000.0);
+
+    assert!(
+        position.x < 200.0,
+        "ship ended up at {position:?}, inside the obstacle"
+    );
+}
+
+#[test]
+fn test_ships_stay_inside_map_walls() {
+    let map_size = Vec2::new(1000.0, 800.0);
+    let mut app = physics_app();
+    for (position, half_extents) in boundary_walls(map_size) {
+        app.world_mut().spawn((
+            Transform::from_xyz(position.x, position.y, 0.0),
+            Collider::cuboid(half_extents.x, half_extents.y),
+        ));
+    }
+
+    let position = fly_ship(app, 5_000.0);
+
+    assert!(
+        position.x.abs() < map_size.x / 2.0,
+        "ship ended up at {position:?}, outside the map"
+    );
+}
+
+/// Spawns two ships the way the game does, as they are at the start of a race with the
+/// given contact mode, and flies them head on at each other. Returns where the left one
+/// ends up.
+fn fly_ships_head_on(ship_contact: ShipContact) -> Vec3 {
+    let mut app = physics_app();
+    let map = MapGenerator::new(1).generate();
+    let class = ShipClasses::default().default_class().clone();
+    let left = Uuid::new_v4();
+
+    let world = app.world_mut();
+    let mut commands = world.commands();
+    for (id, x) in [(left, -200.0), (Uuid::new_v4(), 200.0)] {
+        spawn_ship(
+            &mut commands,
+            Handle::default(),
+            &map,
+            id,
+            &class,
+            Vec2::new(x, 0.0),
+            ship_contact.ships_collide(0.0),
+        );
+    }
+    world.flush();
+
+    let mut left_ship = None;
+    let mut ships = world.query::<(Entity, &ControllableShip, &mut Velocity)>();
+    for (entity, ship, mut velocity) in ships.iter_mut(world) {
+        let direction = if ship.id == left { 1.0 } else { -1.0 };
+        velocity.linvel = Vec2::new(300.0 * direction, 0.0);
+        if ship.id == left {
+            left_ship = Some(entity);
+        }
+    }
+
+    for _ in 0..120 {
+        app.update();
+    }
+
+    app.world()
+        .get::<Transform>(left_ship.unwrap())
+        .unwrap()
+        .translation
+}
+
+#[test]
+fn test_ships_bump_into_each_other() {
+    let position = fly_ships_head_on(ShipContact::Contact);
+    assert!(
+        position.x < 0.0,
+        "ship ended up at {position:?}, past the other ship"
+    );
+}
+
+#[test]
+fn test_ghost_ships_pass_through_each_other() {
+    let position = fly_ships_head_on(ShipContact::Ghost);
+    assert!(
+        position.x > 0.0,
+        "ship ended up at {position:?}, blocked by the other ship"
+    );
+}
+
+#[test]
+fn test_ships_spawned_before_contact_starts_pass_through_each_other() {
+    let position = fly_ships_head_on(ShipContact::ContactAfter { seconds: 10.0 });
+    assert!(
+        position.x > 0.0,
+        "ship ended up at {position:?}, blocked by the other ship"
+    );
+}
+
+/// Flies a ship along the X axis at `speed` through sensors with the given regions, running
+/// `steps` updates with the boost and slow zone system. Returns the ship to inspect.
+fn fly_through_regions(
+    app: &mut App,
+    regions: Vec<(VectorObject, Option<BoostRegion>, Option<SlowRegion>)>,
+    speed: f32,
+) -> Entity {
+    app.add_systems(PostUpdate, handle_boost_and_slow_events);
+    for (zone, boost, slow) in regions {
+        let mut region = app.world_mut().spawn((
+            Transform::from_xyz(zone.position.x, zone.position.y, 0.0),
+            polygon_collider(&zone),
+            Sensor,
+        ));
+        if let Some(boost) = boost {
+            region.insert(boost);
+        }
+        if let Some(slow) = slow {
+            region.insert(slow);
+        }
+    }
+
+    let class = ShipClasses::default().default_class().clone();
+    app.world_mut()
+        .spawn((Transform::default(), ship_physics(&class, true)))
+        .insert(ControllableShip {
+            id: Uuid::new_v4(),
+            class: class.name.clone(),
+            impulse: class.impulse,
+            torque_impulse: class.torque_impulse,
+        })
+        .insert(Velocity::linear(Vec2::new(speed, 0.0)))
+        // No drag of its own, so any change in speed comes from the regions
+        .insert(Damping {
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+        })
+        .id()
+}
+
+#[test]
+fn test_boost_pad_adds_speed_along_its_direction() {
+    let mut app = physics_app();
+    let pad = rectangle(Vec2::new(50.0, 200.0), 50.0, 400.0);
+    let boost = BoostRegion {
+        strength: 300.0,
+        direction: Some(Vec2::X),
+    };
+    let ship = fly_through_regions(&mut app, vec![(pad, Some(boost), None)], 100.0);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    // Boosted once on the way in, not again on the way out
+    let velocity = app.world().get::<Velocity>(ship).unwrap().linvel;
+    assert!(
+        velocity.abs_diff_eq(Vec2::new(400.0, 0.0), 1.0),
+        "ship is flying at {velocity:?}"
+    );
+}
+
+#[test]
+fn test_slow_zone_damping_is_restored_on_exit() {
+    let mut app = physics_app();
+    // Two overlapping zones, so the ship is in one, then both, then the other
+    let regions = vec![
+        (
+            rectangle(Vec2::new(50.0, 200.0), 50.0, 400.0),
+            None,
+            Some(SlowRegion { damping: 1.0 }),
+        ),
+        (
+            rectangle(Vec2::new(80.0, 200.0), 50.0, 400.0),
+            None,
+            Some(SlowRegion { damping: 0.5 }),
+        ),
+    ];
+    let ship = fly_through_regions(&mut app, regions, 300.0);
+
+    let damping = |app: &App| app.world().get::<Damping>(ship).unwrap().linear_damping;
+    for _ in 0..15 {
+        app.update();
+    }
+    assert_eq!(damping(&app), 1.0, "ship should be inside the first zone");
+
+    for _ in 0..60 {
+        app.update();
+    }
+    let position = app.world().get::<Transform>(ship).unwrap().translation;
+    assert!(position.x > 130.0, "ship is still at {position:?}");
+    assert_eq!(damping(&app), 0.0);
+}
@@ -0,0 +1,56 @@
+use crate::ship_class::{ShipClass, ShipClasses, ShipCollider};
+
+fn heavy() -> ShipClass {
+    ShipClasses::default().get("heavy").unwrap().clone()
+}
+
+#[test]
+fn test_ship_classes_load_from_json() {
+    let json = serde_json::to_string(&ShipClasses::default()).unwrap();
+
+    let classes = ShipClasses::from_json(&json).unwrap();
+
+    assert_eq!(classes.0, ShipClasses::default().0);
+    assert!(ShipClasses::from_json("[]").is_err());
+}
+
+#[test]
+fn test_ship_classes_that_cannot_be_simulated_are_rejected() {
+    let json = r#"[{
+        "name": "flat",
+        "mass": 100,
+        "impulse": 8000,
+        "torque_impulse": 8000,
+        "linear_damping": 0.2,
+        "angular_damping": 0.5,
+        "collider": { "shape": "cuboid", "half_width": 10, "half_height": 0 }
+    }]"#;
+    assert!(ShipClasses::from_json(json).is_err());
+
+    let mut class = heavy();
+    class.collider = ShipCollider::Ball { radius: -5.0 };
+    assert!(class.validate().is_err());
+
+    let mut class = heavy();
+    class.impulse = f32::NAN;
+    assert!(class.validate().is_err());
+
+    let mut class = heavy();
+    class.torque_impulse = f32::INFINITY;
+    assert!(class.validate().is_err());
+
+    let mut class = heavy();
+    class.linear_damping = -1.0;
+    assert!(class.validate().is_err());
+
+    // Thrust would push the ship backwards
+    let mut class = heavy();
+    class.impulse = -8000.0;
+    assert!(class.validate().is_err());
+
+    let mut class = heavy();
+    class.torque_impulse = -1.0;
+    assert!(class.validate().is_err());
+
+    assert!(heavy().validate().is_ok());
+}
@@ -0,0 +1,128 @@
+use crate::game_logic::spawn::{respawn_position, spawn_positions, spawn_seed, SHIP_SPACING};
+use crate::map::generator::MapGenerator;
+use crate::map::{Map, VectorObject};
+use bevy::prelude::Vec2;
+use std::path::Path;
+use uuid::Uuid;
+
+const SPAWN_MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.8" orientation="orthogonal" renderorder="right-down" width="1000" height="800" tilewidth="1" tileheight="1" infinite="0">
+ <objectgroup id="1" name="Objects">
+  <object id="1" type="start" x="100" y="100">
+   <polygon points="0,0 200,0 200,200 0,200"/>
+  </object>
+  <object id="2" type="spawn" x="150" y="150">
+   <point/>
+  </object>
+  <object id="3" type="spawn" x="250" y="250">
+   <point/>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+fn assert_spaced(positions: &[Vec2]) {
+    for (i, a) in positions.iter().enumerate() {
+        for b in &positions[i + 1..] {
+            assert!(a.distance(*b) >= SHIP_SPACING, "{a} and {b} overlap");
+        }
+    }
+}
+
+#[test]
+fn test_ships_spawn_apart_inside_start_regions() {
+    let map = MapGenerator::new(7).generate();
+    let positions = spawn_positions(&map, 8, 1);
+
+    assert_eq!(positions.len(), 8);
+    assert_spaced(&positions);
+    for position in &positions {
+        assert!(map.start_regions[0].contains(*position));
+    }
+}
+
+#[test]
+fn test_spawn_points_are_used_first() {
+    let map = Map::from_tmx(Path::new("spawn.tmx"), SPAWN_MAP.as_bytes().to_vec()).unwrap();
+    assert_eq!(map.spawn_points.len(), 2);
+
+    let mut positions = spawn_positions(&map, 2, 3);
+    positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+    assert_eq!(positions, map.spawn_points);
+
+    // Everyone else fills in around them
+    let positions = spawn_positions(&map, 10, 3);
+    assert_eq!(positions.len(), 10);
+    assert_spaced(&positions);
+    for spawn_point in &map.spawn_points {
+        assert!(positions.contains(spawn_point));
+    }
+}
+
+#[test]
+fn test_spawn_positions_are_deterministic() {
+    let map = MapGenerator::new(3).generate();
+    let seed = spawn_seed(Uuid::new_v4());
+
+    assert_eq!(
+        spawn_positions(&map, 5, seed),
+        spawn_positions(&map, 5, seed)
+    );
+
+    // More ships than fit still get their own spot
+    let crowded = spawn_positions(&map, 30, seed);
+    assert_eq!(crowded.len(), 30);
+    assert_spaced(&crowded);
+}
+
+fn rectangle(position: Vec2, width: f32, height: f32) -> VectorObject {
+    VectorObject {
+        position,
+        polygon: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(width, 0.0),
+            Vec2::new(width, height),
+            Vec2::new(0.0, height),
+            Vec2::new(0.0, 0.0),
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_ships_respawning_at_one_checkpoint_are_apart() {
+    let checkpoint = rectangle(Vec2::new(100.0, 100.0), 200.0, 200.0);
+    // Another ship is racing through the middle of the checkpoint
+    let mut occupied = vec![Vec2::new(200.0, 200.0)];
+    for _ in 0..2 {
+        let position = respawn_position(std::slice::from_ref(&checkpoint), None, &occupied);
+        assert!(checkpoint.contains(position));
+        occupied.push(position);
+    }
+    assert_spaced(&occupied);
+
+    // A gate too thin for the grid lines them up across it
+    let gate = rectangle(Vec2::new(500.0, 100.0), 10.0, 300.0);
+    let mut occupied = vec![];
+    for _ in 0..3 {
+        let position = respawn_position(std::slice::from_ref(&gate), None, &occupied);
+        assert!(gate.contains(position), "{position} is off the gate");
+        occupied.push(position);
+    }
+    assert_spaced(&occupied);
+}
+
+#[test]
+fn test_respawn_skips_an_occupied_start_position() {
+    let map = MapGenerator::new(7).generate();
+    let start = spawn_positions(&map, 1, 1)[0];
+
+    assert_eq!(
+        respawn_position(&map.start_regions, Some(start), &[]),
+        start
+    );
+
+    let position = respawn_position(&map.start_regions, Some(start), &[start]);
+    assert!(position.distance(start) >= SHIP_SPACING);
+    assert!(map.start_regions[0].contains(position));
+}